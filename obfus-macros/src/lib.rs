@@ -0,0 +1,158 @@
+//!Obfuscation macros
+//!
+//!Intended to be used via re-exports within `obfus` crate
+
+#![warn(missing_docs)]
+#![allow(clippy::style)]
+
+use proc_macro::TokenStream;
+use quote::quote;
+
+enum Literal {
+    Text(syn::LitStr),
+    Binary(syn::LitByteStr),
+}
+
+struct Obfuscate {
+    literal: Literal,
+    seed: u64,
+}
+
+impl syn::parse::Parse for Obfuscate {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let literal = match input.parse::<syn::Lit>()? {
+            syn::Lit::Str(literal) => Literal::Text(literal),
+            syn::Lit::ByteStr(literal) => Literal::Binary(literal),
+            other => return Err(syn::Error::new(other.span(), "Expected string or byte string literal")),
+        };
+
+        let seed = parse_seed(input)?;
+
+        Ok(Self {
+            literal,
+            seed,
+        })
+    }
+}
+
+struct SecretFromEnv {
+    name: syn::LitStr,
+    seed: u64,
+}
+
+impl syn::parse::Parse for SecretFromEnv {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let name = input.parse::<syn::LitStr>()?;
+        let seed = parse_seed(input)?;
+
+        Ok(Self {
+            name,
+            seed,
+        })
+    }
+}
+
+///Parses `, seed = <int>` with optional trailing comma
+fn parse_seed(input: syn::parse::ParseStream) -> syn::Result<u64> {
+    input.parse::<syn::Token![,]>()?;
+    let name = input.parse::<syn::Ident>()?;
+    if name != "seed" {
+        return Err(syn::Error::new(name.span(), "Expected `seed` argument"));
+    }
+    input.parse::<syn::Token![=]>()?;
+    let seed = input.parse::<syn::LitInt>()?.base10_parse::<u64>()?;
+    //Allow trailing comma
+    if input.peek(syn::Token![,]) {
+        input.parse::<syn::Token![,]>()?;
+    }
+
+    Ok(seed)
+}
+
+#[proc_macro]
+///Obfuscates string or byte string literal using `FisherYates` shuffle with provided `seed`
+///
+///Shuffle is performed at compile time, storing only obfuscated bytes in binary.
+///
+///Expands to expression evaluating to `Secret<N, Text>` for string literal or `Secret<N, Binary>`
+///for byte string literal.
+///
+///Usage: `obfuscate!("my api key", seed = 0x1234)`
+pub fn obfuscate(input: TokenStream) -> TokenStream {
+    let Obfuscate { literal, seed } = syn::parse_macro_input!(input as Obfuscate);
+
+    let bytes = match &literal {
+        Literal::Text(literal) => syn::LitByteStr::new(literal.value().as_bytes(), literal.span()),
+        Literal::Binary(literal) => syn::LitByteStr::new(&literal.value(), literal.span()),
+    };
+    let len = bytes.value().len();
+
+    let secret = match literal {
+        //Content is guaranteed to be utf-8 as it comes from string literal
+        Literal::Text(_) => quote! {
+            unsafe {
+                ::obfus::Secret::<#len, ::obfus::Text>::new(data)
+            }
+        },
+        Literal::Binary(_) => quote! {
+            ::obfus::Secret::<#len, ::obfus::Binary>::new(data)
+        },
+    };
+
+    let expanded = quote! {
+        {
+            const SHUFFLE: ::obfus::shuffle::FisherYates = ::obfus::shuffle::FisherYates::with_seed(#seed);
+            static OBFUSCATED: [u8; #len] = SHUFFLE.shuffle_const(*#bytes);
+
+            //Volatile read to prevent compiler from evaluating reverse at compile time
+            let data = SHUFFLE.reverse_const(unsafe {
+                ::core::ptr::read_volatile(&OBFUSCATED)
+            });
+            #secret
+        }
+    };
+
+    expanded.into()
+}
+
+#[proc_macro]
+///Obfuscates value of compile time environment variable using `FisherYates` shuffle with provided `seed`
+///
+///Variable is read via `env!`, so build fails if it is not set and is re-run whenever it changes.
+///Shuffle is performed at compile time, storing only obfuscated bytes in binary.
+///
+///Expands to expression evaluating to `Secret<N, Text>`.
+///
+///Usage: `secret_from_env!("MY_TOKEN", seed = 0x55)`
+pub fn secret_from_env(input: TokenStream) -> TokenStream {
+    let SecretFromEnv { name, seed } = syn::parse_macro_input!(input as SecretFromEnv);
+    let error = syn::LitStr::new(&format!("secret_from_env!: environment variable `{}` is not set", name.value()), name.span());
+
+    let expanded = quote! {
+        {
+            const RAW: &[u8] = ::core::env!(#name, #error).as_bytes();
+            const LEN: usize = RAW.len();
+            const SHUFFLE: ::obfus::shuffle::FisherYates = ::obfus::shuffle::FisherYates::with_seed(#seed);
+            static OBFUSCATED: [u8; LEN] = SHUFFLE.shuffle_const({
+                let mut data = [0u8; LEN];
+                let mut idx = 0;
+                while idx < LEN {
+                    data[idx] = RAW[idx];
+                    idx += 1;
+                }
+                data
+            });
+
+            //Volatile read to prevent compiler from evaluating reverse at compile time
+            let data = SHUFFLE.reverse_const(unsafe {
+                ::core::ptr::read_volatile(&OBFUSCATED)
+            });
+            //Content is guaranteed to be utf-8 as it comes from `env!`
+            unsafe {
+                ::obfus::Secret::<LEN, ::obfus::Text>::new(data)
+            }
+        }
+    };
+
+    expanded.into()
+}
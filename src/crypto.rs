@@ -1,37 +1,339 @@
 //!Necessary Crypto functions
 
-use crate::utils;
-use aes_gcm::Aes256Gcm;
+use crate::{prng, utils};
 
-const NONCE_SIZE: usize = 12;
-pub(crate) const TAG_SIZE: usize = 16;
+use core::{ops, slice};
+pub use aes_gcm::{Aes128Gcm, Aes256Gcm};
+
+pub(crate) const NONCE_SIZE: usize = 12;
+///Size of AEAD authentication tag
+pub const TAG_SIZE: usize = 16;
+
+///Size of original length stored by [Buffer::pad_to](struct.Buffer.html#method.pad_to)
+pub const PADDING_LEN_SIZE: usize = 4;
 
 ///Calculates buffer size to hold `size` data (it adds size of AEAD tag to be appended)
 pub const fn required_buffer_size(size: usize) -> usize {
     size + TAG_SIZE
 }
 
+///Calculates total buffer size to hold multiple records, each encrypted separately with its own tag
+///
+///Returns `0` for empty `sizes`, while overflow saturates to `usize::MAX`
+pub const fn required_buffer_size_many(sizes: &[usize]) -> usize {
+    let mut total = 0usize;
+    let mut idx = 0;
+    while idx < sizes.len() {
+        total = total.saturating_add(sizes[idx].saturating_add(TAG_SIZE));
+        idx += 1;
+    }
+    total
+}
+
+///Calculates buffer size to hold `size` data encrypted by cipher `C`, using its tag size
+///
+///`TAG` must match cipher's tag, e.g. `required_buffer_size_for::<CryptoTag12, 12>(size)`, otherwise it fails to compile.
+pub const fn required_buffer_size_for<C: Cipher<TAG>, const TAG: usize>(size: usize) -> usize {
+    size + C::TAG_SIZE
+}
+
+#[macro_export]
+///Asserts at compile time that buffer of size `N` can hold `DATA_LEN` bytes of data together with AEAD tag
+///
+///Usage: `assert_buffer_fits!(DATA_LEN, N)`
+macro_rules! assert_buffer_fits {
+    ($data_len:expr, $n:expr $(,)?) => {
+        const _: () = assert!(
+            $n >= $crate::crypto::required_buffer_size($data_len),
+            concat!("assert_buffer_fits!: buffer size ", stringify!($n), " is smaller than required_buffer_size(", stringify!($data_len), ")")
+        );
+    };
+}
+pub use assert_buffer_fits;
+
+#[cfg(feature = "os_rng")]
+#[inline]
+///Generates random nonce using OS random number generator
+///
+///**IMPORTANT**: nonce must never be re-used with the same key, and it must be stored alongside
+///ciphertext as it is required for decryption.
+///
+///Returns error if OS is unable to provide entropy
+pub fn random_nonce() -> Result<[u8; NONCE_SIZE], getrandom::Error> {
+    let mut nonce = [0; NONCE_SIZE];
+    getrandom::fill(&mut nonce)?;
+    Ok(nonce)
+}
+
+#[cfg(feature = "kdf")]
+#[inline]
+///Derives key suitable for [Crypto](struct.Crypto.html) from `password` using PBKDF2-HMAC-SHA256
+///
+///- `salt` - Arbitrary length salt, recommended to be random and at least 16 bytes long.
+///- `iterations` - Number of rounds, recommended to be at least `600_000`.
+///
+///Note that resulting key is plain array, consider zeroing it after passing to [Crypto](struct.Crypto.html)
+pub fn derive_key(password: &[u8], salt: &[u8], iterations: u32) -> [u8; 32] {
+    pbkdf2::pbkdf2_hmac_array::<pbkdf2::sha2::Sha256, 32>(password, salt, iterations)
+}
+
+#[cfg(feature = "hash")]
+///Derives nonce from `context` and `counter` as truncated `SHA-256(len(context) || context || counter)`
+///
+///Nonce is deterministic, hence it is unique as long as every `(context, counter)` pair is unique
+///for the same key, with collision probability of distinct pairs bounded by 96 bits digest.
+///
+///Both length and counter are encoded as big-endian `u64`.
+pub fn derive_nonce(context: &[u8], counter: u64) -> [u8; NONCE_SIZE] {
+    use sha2::Digest;
+
+    let mut hasher = sha2::Sha256::new();
+    hasher.update((context.len() as u64).to_be_bytes());
+    hasher.update(context);
+    hasher.update(counter.to_be_bytes());
+    let digest = hasher.finalize();
+
+    let mut nonce = [0; NONCE_SIZE];
+    nonce.copy_from_slice(&digest[..NONCE_SIZE]);
+    nonce
+}
+
+///Monotonic nonce generator
+///
+///Nonce is composed of 4 bytes prefix followed by big-endian encoded `u64` counter.
+///
+///Generation stops once counter is exhausted in order to never re-use nonce.
+pub struct NonceSequence {
+    prefix: [u8; 4],
+    counter: u64,
+}
+
+impl NonceSequence {
+    #[inline(always)]
+    ///Creates new instance with zero prefix
+    pub const fn new() -> Self {
+        Self::with_prefix([0; 4])
+    }
+
+    #[inline(always)]
+    ///Creates new instance with provided `prefix`
+    ///
+    ///Prefix is recommended to be random, when the same key is used by multiple generators
+    pub const fn with_prefix(prefix: [u8; 4]) -> Self {
+        Self::with_counter(prefix, 0)
+    }
+
+    #[inline(always)]
+    ///Creates new instance with provided `prefix` starting from `counter`
+    ///
+    ///Useful to resume sequence from previously stored counter
+    pub const fn with_counter(prefix: [u8; 4], counter: u64) -> Self {
+        Self {
+            prefix,
+            counter,
+        }
+    }
+
+    #[inline(always)]
+    ///Returns counter that is going to be used for next nonce
+    pub const fn counter(&self) -> u64 {
+        self.counter
+    }
+
+    ///Generates next nonce, returning `None` once counter is exhausted
+    pub const fn next(&mut self) -> Option<[u8; NONCE_SIZE]> {
+        let next_counter = match self.counter.checked_add(1) {
+            Some(next_counter) => next_counter,
+            None => return None,
+        };
+        let counter = self.counter.to_be_bytes();
+        self.counter = next_counter;
+
+        let mut nonce = [0; NONCE_SIZE];
+        let mut idx = 0;
+        while idx < self.prefix.len() {
+            nonce[idx] = self.prefix[idx];
+            idx += 1;
+        }
+        while idx < NONCE_SIZE {
+            nonce[idx] = counter[idx - self.prefix.len()];
+            idx += 1;
+        }
+
+        Some(nonce)
+    }
+}
+
 ///Buffer to store [Crypto](struct.Crypto.html) output
 ///
+///`TAG` is size of authentication tag reserved at the end of buffer, which is 16 bytes unless cipher uses truncated tag.
+///
 ///Note that buffer's capacity should be calculated using [required_buffer_size](fn.required_buffer_size.html)
-pub struct Buffer<const N: usize> {
+pub struct Buffer<const N: usize, const TAG: usize = TAG_SIZE> {
     data: [u8; N],
     len: usize,
 }
 
-impl<const N: usize> Buffer<N> {
-    const DATA_SIZE: usize = N - TAG_SIZE;
+impl<const N: usize, const TAG: usize> Buffer<N, TAG> {
+    const DATA_SIZE: usize = N - TAG;
 
     #[inline]
     ///Creates new instance
     pub const fn new() -> Self {
-        debug_assert!(Self::DATA_SIZE > 0, "Buffer capacity should be greater than 16 bytes");
+        const {
+            assert!(N > TAG, "Buffer capacity should be greater than tag size");
+        }
         Self {
             data: [0; N],
             len: 0,
         }
     }
 
+    ///Creates new instance with `data` copied in
+    ///
+    ///Fails if `data` exceeds [capacity](#method.capacity), leaving nothing written
+    pub fn from_slice(data: &[u8]) -> Result<Self, aes_gcm::Error> {
+        if data.len() > Self::DATA_SIZE {
+            return Err(aes_gcm::Error);
+        }
+
+        let mut result = Self::new();
+        result.data[..data.len()].copy_from_slice(data);
+        result.len = data.len();
+        Ok(result)
+    }
+
+    //Replaces content with `data`, failing if it exceeds capacity
+    fn replace_with(&mut self, data: &[u8]) -> Result<(), aes_gcm::Error> {
+        if data.len() > Self::DATA_SIZE {
+            return Err(aes_gcm::Error);
+        }
+
+        self.clear();
+        self.data[..data.len()].copy_from_slice(data);
+        self.len = data.len();
+        Ok(())
+    }
+
+    #[inline(always)]
+    ///Returns capacity available for data, excluding space reserved for AEAD tag
+    pub const fn capacity(&self) -> usize {
+        Self::DATA_SIZE
+    }
+
+    #[inline]
+    ///Appends single `byte`
+    ///
+    ///Fails if buffer is already filled up to [capacity](#method.capacity)
+    pub fn push(&mut self, byte: u8) -> Result<(), aes_gcm::Error> {
+        if self.len >= Self::DATA_SIZE {
+            return Err(aes_gcm::Error);
+        }
+
+        self.data[self.len] = byte;
+        self.len += 1;
+        Ok(())
+    }
+
+    ///Appends every byte of `iter`
+    ///
+    ///Fails if `iter` yields more bytes than remaining [capacity](#method.capacity), in which case
+    ///already appended bytes are zeroed and buffer is restored to its original length
+    pub fn extend_from_iter<I: IntoIterator<Item = u8>>(&mut self, iter: I) -> Result<(), aes_gcm::Error> {
+        let original_len = self.len;
+
+        for byte in iter {
+            if let Err(error) = self.push(byte) {
+                utils::secure_zero(&mut self.data[original_len..self.len]);
+                self.len = original_len;
+                return Err(error);
+            }
+        }
+
+        Ok(())
+    }
+
+    ///Resizes buffer to `new_len`
+    ///
+    ///When growing, new bytes are set to `value`, while on shrinking removed bytes are zeroed.
+    ///
+    ///Fails if `new_len` exceeds `N`, leaving buffer unchanged
+    pub fn resize(&mut self, new_len: usize, value: u8) -> Result<(), aes_gcm::Error> {
+        if new_len > N {
+            return Err(aes_gcm::Error);
+        }
+
+        if new_len > self.len {
+            self.data[self.len..new_len].fill(value);
+        } else {
+            utils::secure_zero(&mut self.data[new_len..self.len]);
+        }
+        self.len = new_len;
+        Ok(())
+    }
+
+    ///Pads data up to `target_len` with random decoy bytes, followed by original length
+    ///
+    ///Original length is stored as little-endian `u32` within last [PADDING_LEN_SIZE](constant.PADDING_LEN_SIZE.html) bytes,
+    ///so that it is encrypted together with data, making all ciphertexts of the same `target_len`.
+    ///After decryption use [unpadded_len](#method.unpadded_len) to retrieve it for [strip_padding](#method.strip_padding).
+    ///
+    ///Fails if `target_len` exceeds [capacity](#method.capacity) or cannot fit data with its length, leaving buffer unchanged
+    pub fn pad_to(&mut self, target_len: usize, rng: &mut prng::Squares) -> Result<(), aes_gcm::Error> {
+        let real_len = self.len;
+        if target_len > Self::DATA_SIZE || target_len < real_len.saturating_add(PADDING_LEN_SIZE) || real_len > u32::MAX as usize {
+            return Err(aes_gcm::Error);
+        }
+
+        let len_start = target_len - PADDING_LEN_SIZE;
+        rng.fill_bytes(&mut self.data[real_len..len_start]);
+        self.data[len_start..target_len].copy_from_slice(&(real_len as u32).to_le_bytes());
+        self.len = target_len;
+        Ok(())
+    }
+
+    ///Returns original length stored by [pad_to](#method.pad_to)
+    ///
+    ///Returns `None` if stored length is not consistent with written data, which means buffer is not padded
+    pub fn unpadded_len(&self) -> Option<usize> {
+        let len_start = self.len.checked_sub(PADDING_LEN_SIZE)?;
+        let mut real_len = [0; PADDING_LEN_SIZE];
+        real_len.copy_from_slice(&self.data[len_start..self.len]);
+        let real_len = u32::from_le_bytes(real_len) as usize;
+
+        if real_len <= len_start {
+            Some(real_len)
+        } else {
+            None
+        }
+    }
+
+    ///Strips padding added by [pad_to](#method.pad_to), truncating data to `real_len` and zeroing decoy bytes
+    ///
+    ///Fails if `real_len` exceeds written data, leaving buffer unchanged
+    pub fn strip_padding(&mut self, real_len: usize) -> Result<(), aes_gcm::Error> {
+        if real_len > self.len {
+            return Err(aes_gcm::Error);
+        }
+
+        self.resize(real_len, 0)
+    }
+
+    #[inline(always)]
+    ///Returns length of plaintext, assuming buffer holds ciphertext with tag appended
+    ///
+    ///Returns `None` if written data is shorter than tag, which means it cannot be ciphertext
+    pub const fn plaintext_len(&self) -> Option<usize> {
+        self.len.checked_sub(TAG)
+    }
+
+    #[inline]
+    ///Zeroes written data, resetting buffer to be empty
+    pub fn clear(&mut self) {
+        utils::secure_zero(self.data_mut());
+        self.len = 0;
+    }
+
     #[inline]
     ///Access written data
     pub fn data(&self) -> &[u8] {
@@ -45,21 +347,80 @@ impl<const N: usize> Buffer<N> {
     }
 }
 
-impl<const N: usize> AsRef<[u8]> for Buffer<N> {
+impl<const N: usize, const TAG: usize> AsRef<[u8]> for Buffer<N, TAG> {
     #[inline]
     fn as_ref(&self) -> &[u8] {
         self.data()
     }
 }
 
-impl<const N: usize> AsMut<[u8]> for Buffer<N> {
+impl<const N: usize, const TAG: usize> AsMut<[u8]> for Buffer<N, TAG> {
     #[inline]
     fn as_mut(&mut self) -> &mut [u8] {
         self.data_mut()
     }
 }
 
-impl<const N: usize> aes_gcm::aead::Buffer for Buffer<N> {
+///Compares written data
+///
+///Comparison is not constant time, as buffer is intended to hold ciphertext.
+///Use [secure_compare](../utils/fn.secure_compare.html) when buffer holds decrypted data.
+impl<const N: usize, const TAG: usize> PartialEq for Buffer<N, TAG> {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        self.data() == other.data()
+    }
+}
+
+impl<const N: usize, const TAG: usize> Eq for Buffer<N, TAG> {}
+
+impl<const N: usize, const TAG: usize> PartialEq<[u8]> for Buffer<N, TAG> {
+    #[inline(always)]
+    fn eq(&self, other: &[u8]) -> bool {
+        self.data() == other
+    }
+}
+
+impl<const N: usize, const TAG: usize> PartialEq<&[u8]> for Buffer<N, TAG> {
+    #[inline(always)]
+    fn eq(&self, other: &&[u8]) -> bool {
+        self.data() == *other
+    }
+}
+
+impl<const N: usize, const TAG: usize, const M: usize> PartialEq<[u8; M]> for Buffer<N, TAG> {
+    #[inline(always)]
+    fn eq(&self, other: &[u8; M]) -> bool {
+        self.data() == other
+    }
+}
+
+impl<const N: usize, const TAG: usize, const M: usize> PartialEq<&[u8; M]> for Buffer<N, TAG> {
+    #[inline(always)]
+    fn eq(&self, other: &&[u8; M]) -> bool {
+        self.data() == *other
+    }
+}
+
+///Prints only length of written data, as buffer may hold decrypted data
+impl<const N: usize, const TAG: usize> core::fmt::Debug for Buffer<N, TAG> {
+    #[inline]
+    fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(fmt, "Buffer<{N}>({} bytes)", self.len)
+    }
+}
+
+//Mutable indexing is omitted on purpose, use data_mut() instead
+impl<const N: usize, const TAG: usize, I: slice::SliceIndex<[u8]>> ops::Index<I> for Buffer<N, TAG> {
+    type Output = I::Output;
+
+    #[inline(always)]
+    fn index(&self, index: I) -> &Self::Output {
+        &self.data()[index]
+    }
+}
+
+impl<const N: usize, const TAG: usize> aes_gcm::aead::Buffer for Buffer<N, TAG> {
     fn extend_from_slice(&mut self, other: &[u8]) -> aes_gcm::aead::Result<()> {
         let remaining_capacity = N.saturating_sub(self.len);
         if other.len() <= remaining_capacity {
@@ -76,7 +437,7 @@ impl<const N: usize> aes_gcm::aead::Buffer for Buffer<N> {
         debug_assert!(len < N);
 
         if len < self.len {
-            utils::secure_memset(&mut self.data_mut()[len..], 0);
+            utils::secure_zero(&mut self.data_mut()[len..]);
             self.len = len;
         }
     }
@@ -91,41 +452,722 @@ impl<const N: usize> aes_gcm::aead::Buffer for Buffer<N> {
     }
 }
 
+#[cfg(feature = "zeroize")]
+impl<const N: usize, const TAG: usize> zeroize::Zeroize for Buffer<N, TAG> {
+    #[inline]
+    fn zeroize(&mut self) {
+        utils::secure_zero(&mut self.data);
+        self.len = 0;
+    }
+}
+
+///Common interface of AEAD ciphers
+///
+///Allows to use ciphers with different nonce sizes (e.g. 12 bytes of AES-GCM and 24 bytes of
+///XChaCha20-Poly1305) via the same generic code.
+///
+///`TAG` is size of authentication tag, which is 16 bytes unless cipher uses truncated tag.
+pub trait Cipher<const TAG: usize = TAG_SIZE> {
+    ///Size of nonce in bytes
+    const NONCE_SIZE: usize;
+    ///Size of authentication tag in bytes
+    const TAG_SIZE: usize = TAG;
+    ///Nonce type, which is array of [NONCE_SIZE](#associatedconstant.NONCE_SIZE) bytes
+    type Nonce: Copy + AsRef<[u8]>;
+
+    #[inline(always)]
+    ///Encrypts content inside `buffer`
+    ///
+    ///Note that buffer's capacity should be calculated using [required_buffer_size](fn.required_buffer_size.html)
+    fn encrypt<const N: usize>(&self, nonce: Self::Nonce, in_out: &mut Buffer<N, TAG>) -> Result<(), aes_gcm::Error> {
+        self.encrypt_with_aad(nonce, &[], in_out)
+    }
+
+    ///Encrypts content inside `buffer`, authenticating it together with associated data `aad`
+    fn encrypt_with_aad<const N: usize>(&self, nonce: Self::Nonce, aad: &[u8], in_out: &mut Buffer<N, TAG>) -> Result<(), aes_gcm::Error>;
+
+    #[inline(always)]
+    ///Decrypts content inside `buffer`
+    ///
+    ///On success `in_out` length will be truncated to the size of original data
+    fn decrypt<const N: usize>(&self, nonce: Self::Nonce, in_out: &mut Buffer<N, TAG>) -> Result<(), aes_gcm::Error> {
+        self.decrypt_with_aad(nonce, &[], in_out)
+    }
+
+    ///Decrypts content inside `buffer`, verifying it against associated data `aad`
+    fn decrypt_with_aad<const N: usize>(&self, nonce: Self::Nonce, aad: &[u8], in_out: &mut Buffer<N, TAG>) -> Result<(), aes_gcm::Error>;
+}
+
+macro_rules! impl_aead {
+    ($name:ident, $cipher:ty, $key_size:literal, $nonce_size:literal, $tag_size:literal) => {
+        //Cipher zeroes its state on drop
+        #[cfg(feature = "zeroize")]
+        impl zeroize::ZeroizeOnDrop for $name {}
+
+        impl $name {
+            #[inline]
+            ///Creates new instance using provided key
+            pub fn new(key: [u8; $key_size]) -> Self {
+                use aes_gcm::KeyInit;
+
+                Self {
+                    cipher: <$cipher>::new(&(key.into()))
+                }
+            }
+
+            #[inline]
+            ///Encrypts content inside `buffer`
+            ///
+            ///Note that buffer's capacity should be calculated using [required_buffer_size](fn.required_buffer_size.html)
+            pub fn encrypt<const N: usize>(&self, nonce: [u8; $nonce_size], in_out: &mut Buffer<N, $tag_size>) -> Result<(), aes_gcm::Error> {
+                self.encrypt_with_aad(nonce, &[], in_out)
+            }
+
+            ///Encrypts content inside `buffer`, authenticating it together with associated data `aad`
+            ///
+            ///Note that buffer's capacity should be calculated using [required_buffer_size](fn.required_buffer_size.html)
+            ///
+            ///On failure written data is securely zeroed (retaining its length), so that plaintext never remains in the buffer.
+            pub fn encrypt_with_aad<const N: usize>(&self, nonce: [u8; $nonce_size], aad: &[u8], in_out: &mut Buffer<N, $tag_size>) -> Result<(), aes_gcm::Error> {
+                use aes_gcm::AeadInOut;
+
+                let result = self.cipher.encrypt_in_place(&(nonce.into()), aad, in_out);
+                if result.is_err() {
+                    utils::secure_memset(in_out.data_mut(), 0);
+                }
+                result
+            }
+
+            #[inline]
+            ///Decrypts content inside `buffer`
+            ///
+            ///Note that buffer's capacity should be calculated using [required_buffer_size](fn.required_buffer_size.html)
+            ///
+            ///On success `in_out` length will be truncated to the size of original data
+            pub fn decrypt<const N: usize>(&self, nonce: [u8; $nonce_size], in_out: &mut Buffer<N, $tag_size>) -> Result<(), aes_gcm::Error> {
+                self.decrypt_with_aad(nonce, &[], in_out)
+            }
+
+            #[inline]
+            ///Decrypts content inside `buffer`, verifying it against associated data `aad`
+            ///
+            ///Note that `aad` must be the same as one used during encryption.
+            ///
+            ///On success `in_out` length will be truncated to the size of original data
+            pub fn decrypt_with_aad<const N: usize>(&self, nonce: [u8; $nonce_size], aad: &[u8], in_out: &mut Buffer<N, $tag_size>) -> Result<(), aes_gcm::Error> {
+                use aes_gcm::AeadInOut;
+
+                self.cipher.decrypt_in_place(&(nonce.into()), aad, in_out)
+            }
+
+            #[inline]
+            ///Encrypts `plaintext` into `out`, leaving source untouched
+            ///
+            ///Previous content of `out` is replaced.
+            ///
+            ///Note that buffer's capacity should be calculated using [required_buffer_size](fn.required_buffer_size.html)
+            pub fn encrypt_to<const N: usize>(&self, nonce: [u8; $nonce_size], plaintext: &[u8], out: &mut Buffer<N, $tag_size>) -> Result<(), aes_gcm::Error> {
+                out.replace_with(plaintext)?;
+                self.encrypt(nonce, out)
+            }
+
+            #[inline]
+            ///Decrypts `ciphertext` (with tag appended) into `out`, leaving source untouched
+            ///
+            ///Previous content of `out` is replaced.
+            ///
+            ///On success `out` length will be the size of original data
+            pub fn decrypt_to<const N: usize>(&self, nonce: [u8; $nonce_size], ciphertext: &[u8], out: &mut Buffer<N, $tag_size>) -> Result<(), aes_gcm::Error> {
+                use aes_gcm::aead::Buffer as _;
+
+                //Ciphertext includes tag, hence it may fill the whole buffer
+                if ciphertext.len() > N {
+                    return Err(aes_gcm::Error);
+                }
+                out.clear();
+                out.extend_from_slice(ciphertext)?;
+                self.decrypt(nonce, out)
+            }
+
+            ///Decrypts content inside `buffer`, zeroing it on failure
+            ///
+            ///Authentication tag is verified in constant time before any plaintext is produced, hence
+            ///time taken by failure path does not depend on content.
+            ///On failure written data is securely zeroed (retaining its length), so that neither
+            ///ciphertext nor partially decrypted plaintext remain in the buffer.
+            ///
+            ///On success `in_out` length will be truncated to the size of original data
+            pub fn decrypt_ct<const N: usize>(&self, nonce: [u8; $nonce_size], in_out: &mut Buffer<N, $tag_size>) -> Result<(), aes_gcm::Error> {
+                let result = self.decrypt(nonce, in_out);
+                if result.is_err() {
+                    utils::secure_zero(in_out.data_mut());
+                }
+                result
+            }
+
+            ///Encrypts content of `in_out` without appending authentication tag, returning it instead
+            ///
+            ///Tag must be stored alongside ciphertext as it is required for decryption
+            ///
+            ///On failure `in_out` is securely zeroed
+            pub fn encrypt_detached(&self, nonce: [u8; $nonce_size], in_out: &mut [u8]) -> Result<[u8; $tag_size], aes_gcm::Error> {
+                use aes_gcm::AeadInOut;
+
+                let result = self.cipher.encrypt_inout_detached(&(nonce.into()), &[], in_out.into()).map(Into::into);
+                if result.is_err() {
+                    utils::secure_memset(in_out, 0);
+                }
+                result
+            }
+
+            #[inline]
+            ///Decrypts content of `in_out` verifying it against authentication `tag`
+            pub fn decrypt_detached(&self, nonce: [u8; $nonce_size], in_out: &mut [u8], tag: [u8; $tag_size]) -> Result<(), aes_gcm::Error> {
+                use aes_gcm::AeadInOut;
+
+                self.cipher.decrypt_inout_detached(&(nonce.into()), &[], in_out.into(), &(tag.into()))
+            }
+        }
+
+        impl Cipher<$tag_size> for $name {
+            const NONCE_SIZE: usize = $nonce_size;
+            type Nonce = [u8; $nonce_size];
+
+            #[inline(always)]
+            fn encrypt_with_aad<const N: usize>(&self, nonce: Self::Nonce, aad: &[u8], in_out: &mut Buffer<N, $tag_size>) -> Result<(), aes_gcm::Error> {
+                $name::encrypt_with_aad(self, nonce, aad, in_out)
+            }
+
+            #[inline(always)]
+            fn decrypt_with_aad<const N: usize>(&self, nonce: Self::Nonce, aad: &[u8], in_out: &mut Buffer<N, $tag_size>) -> Result<(), aes_gcm::Error> {
+                $name::decrypt_with_aad(self, nonce, aad, in_out)
+            }
+        }
+    };
+}
+
 ///AES-256 wrapper
+///
+///On drop key schedule is zeroed
 pub struct Crypto {
-    aes: Aes256Gcm
+    cipher: Aes256Gcm
 }
 
+impl_aead!(Crypto, Aes256Gcm, 32, 12, 16);
+
 impl Crypto {
+    ///Encrypts every buffer within `buffers`, each with fresh nonce from `nonces`
+    ///
+    ///Stops on first failure (including exhaustion of `nonces`), returning index of failed buffer.
+    ///Buffers before it remain encrypted, while buffers after it are left untouched.
+    ///
+    ///Nonce of buffer `idx` is the `idx`th nonce generated by `nonces`, hence caller should remember
+    ///counter of `nonces` prior to the call in order to decrypt.
+    pub fn encrypt_batch<const N: usize>(&self, nonces: &mut NonceSequence, buffers: &mut [Buffer<N>]) -> Result<(), usize> {
+        for (idx, buffer) in buffers.iter_mut().enumerate() {
+            let nonce = match nonces.next() {
+                Some(nonce) => nonce,
+                None => return Err(idx),
+            };
+            if self.encrypt(nonce, buffer).is_err() {
+                return Err(idx);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+///AES-128 wrapper
+///
+///Uses the same 16 bytes tag and 12 bytes nonce as [Crypto](struct.Crypto.html), hence buffer size is still calculated via [required_buffer_size](fn.required_buffer_size.html).
+///
+///On drop key schedule is zeroed
+pub struct Crypto128 {
+    cipher: Aes128Gcm
+}
+
+impl_aead!(Crypto128, Aes128Gcm, 16, 12, 16);
+
+///AES-256 wrapper with authentication tag truncated to 12 bytes
+///
+///**IMPORTANT**: truncated tag reduces authentication strength, making forgery easier:
+///with `t` bits tag attacker can succeed with probability about `2^-t` per attempt, and chance
+///grows with amount of data encrypted by the same key. Use it only when protocol requires it.
+///
+///Requires [Buffer](struct.Buffer.html) reserving 12 bytes for tag (i.e. `Buffer<N, 12>`), with size calculated via [required_buffer_size_for](fn.required_buffer_size_for.html).
+///
+///On drop key schedule is zeroed
+pub struct CryptoTag12 {
+    cipher: aes_gcm::AesGcm<aes_gcm::aes::Aes256, aes_gcm::aead::consts::U12, aes_gcm::aead::consts::U12>
+}
+
+impl_aead!(CryptoTag12, aes_gcm::AesGcm<aes_gcm::aes::Aes256, aes_gcm::aead::consts::U12, aes_gcm::aead::consts::U12>, 32, 12, 12);
+
+#[cfg(feature = "hazmat")]
+///AES-256 wrapper with authentication tag truncated to 8 bytes
+///
+///**IMPORTANT**: 8 bytes tag provides weak authentication, refer to [CryptoTag12](struct.CryptoTag12.html) for details.
+///
+///On drop key schedule is zeroed
+pub struct CryptoTag8 {
+    cipher: aes_gcm::AesGcm<aes_gcm::aes::Aes256, aes_gcm::aead::consts::U12, aes_gcm::aead::consts::U8>
+}
+
+#[cfg(feature = "hazmat")]
+impl_aead!(CryptoTag8, aes_gcm::AesGcm<aes_gcm::aes::Aes256, aes_gcm::aead::consts::U12, aes_gcm::aead::consts::U8>, 32, 12, 8);
+
+///Size of nonce prefix used by [StreamEncryptor](struct.StreamEncryptor.html) and [StreamDecryptor](struct.StreamDecryptor.html)
+pub const STREAM_NONCE_SIZE: usize = NONCE_SIZE - 5;
+
+#[inline(always)]
+const fn stream_nonce(prefix: &[u8; STREAM_NONCE_SIZE], counter: u32, is_last: bool) -> [u8; NONCE_SIZE] {
+    let counter = counter.to_be_bytes();
+    let mut nonce = [0; NONCE_SIZE];
+    let mut idx = 0;
+    while idx < STREAM_NONCE_SIZE {
+        nonce[idx] = prefix[idx];
+        idx += 1;
+    }
+    while idx < NONCE_SIZE - 1 {
+        nonce[idx] = counter[idx - STREAM_NONCE_SIZE];
+        idx += 1;
+    }
+    nonce[NONCE_SIZE - 1] = is_last as u8;
+    nonce
+}
+
+///Chunked encryption using STREAM construction
+///
+///Each chunk is encrypted with nonce composed of `prefix`, big-endian chunk counter and final
+///chunk flag, which allows [StreamDecryptor](struct.StreamDecryptor.html) to detect re-ordered,
+///removed or truncated chunks.
+///
+///Reference: <https://eprint.iacr.org/2015/189.pdf>
+pub struct StreamEncryptor {
+    crypto: Crypto,
+    prefix: [u8; STREAM_NONCE_SIZE],
+    counter: u32,
+}
+
+impl StreamEncryptor {
+    #[inline]
+    ///Creates new instance with nonce `prefix`
+    ///
+    ///Prefix must be unique for every stream encrypted with the same key
+    pub const fn new(crypto: Crypto, prefix: [u8; STREAM_NONCE_SIZE]) -> Self {
+        Self {
+            crypto,
+            prefix,
+            counter: 0,
+        }
+    }
+
+    ///Encrypts next chunk inside `in_out`
+    ///
+    ///Fails if chunk counter is exhausted
+    pub fn encrypt_next<const N: usize>(&mut self, in_out: &mut Buffer<N>) -> Result<(), aes_gcm::Error> {
+        let next_counter = match self.counter.checked_add(1) {
+            Some(next_counter) => next_counter,
+            None => return Err(aes_gcm::Error),
+        };
+        self.crypto.encrypt(stream_nonce(&self.prefix, self.counter, false), in_out)?;
+        self.counter = next_counter;
+        Ok(())
+    }
+
+    #[inline]
+    ///Encrypts final chunk inside `in_out`, finishing stream
+    pub fn encrypt_last<const N: usize>(self, in_out: &mut Buffer<N>) -> Result<(), aes_gcm::Error> {
+        self.crypto.encrypt(stream_nonce(&self.prefix, self.counter, true), in_out)
+    }
+}
+
+///Chunked decryption using STREAM construction
+///
+///Refer to [StreamEncryptor](struct.StreamEncryptor.html) for details
+pub struct StreamDecryptor {
+    crypto: Crypto,
+    prefix: [u8; STREAM_NONCE_SIZE],
+    counter: u32,
+}
+
+impl StreamDecryptor {
+    #[inline]
+    ///Creates new instance with nonce `prefix`, that was used to encrypt stream
+    pub const fn new(crypto: Crypto, prefix: [u8; STREAM_NONCE_SIZE]) -> Self {
+        Self {
+            crypto,
+            prefix,
+            counter: 0,
+        }
+    }
+
+    ///Decrypts next chunk inside `in_out`
+    ///
+    ///Fails if chunk is not authentic, is out of order, or is the final chunk
+    pub fn decrypt_next<const N: usize>(&mut self, in_out: &mut Buffer<N>) -> Result<(), aes_gcm::Error> {
+        let next_counter = match self.counter.checked_add(1) {
+            Some(next_counter) => next_counter,
+            None => return Err(aes_gcm::Error),
+        };
+        self.crypto.decrypt(stream_nonce(&self.prefix, self.counter, false), in_out)?;
+        self.counter = next_counter;
+        Ok(())
+    }
+
     #[inline]
+    ///Decrypts final chunk inside `in_out`, finishing stream
+    ///
+    ///Fails if chunk is not authentic or is not the final chunk, which indicates truncated stream
+    pub fn decrypt_last<const N: usize>(self, in_out: &mut Buffer<N>) -> Result<(), aes_gcm::Error> {
+        self.crypto.decrypt(stream_nonce(&self.prefix, self.counter, true), in_out)
+    }
+}
+
+///Size of [SealedMessage](struct.SealedMessage.html) header: version byte followed by nonce
+pub const SEALED_HEADER_SIZE: usize = 1 + NONCE_SIZE;
+
+///Complete sealed message, bundling version, nonce and ciphertext with tag
+///
+///Serialized layout is `version || nonce || ciphertext || tag`.
+///
+///Version byte is authenticated as associated data, hence tampering with it (e.g. to downgrade
+///message) makes [open](#method.open) to fail.
+///
+///Note that `N` should be calculated using [required_buffer_size](fn.required_buffer_size.html)
+pub struct SealedMessage<const N: usize> {
+    version: u8,
+    nonce: [u8; NONCE_SIZE],
+    ciphertext: Buffer<N>,
+}
+
+impl<const N: usize> SealedMessage<N> {
+    ///Encrypts `plaintext` into new message with provided `version` and `nonce`
+    ///
+    ///Fails if `plaintext` exceeds buffer's capacity
+    pub fn seal(crypto: &Crypto, version: u8, nonce: [u8; NONCE_SIZE], plaintext: &[u8]) -> Result<Self, aes_gcm::Error> {
+        let mut ciphertext = Buffer::from_slice(plaintext)?;
+        crypto.encrypt_with_aad(nonce, &[version], &mut ciphertext)?;
+
+        Ok(Self {
+            version,
+            nonce,
+            ciphertext,
+        })
+    }
+
+    ///Decrypts message, returning buffer with plaintext
+    ///
+    ///Fails if message is not authentic, including when version byte is modified.
+    pub fn open(&self, crypto: &Crypto) -> Result<Buffer<N>, aes_gcm::Error> {
+        let mut result = Buffer::new();
+        result.data[..self.ciphertext.len].copy_from_slice(self.ciphertext.data());
+        result.len = self.ciphertext.len;
+
+        crypto.decrypt_with_aad(self.nonce, &[self.version], &mut result)?;
+        Ok(result)
+    }
+
+    #[inline(always)]
+    ///Returns message version
+    pub const fn version(&self) -> u8 {
+        self.version
+    }
+
+    #[inline(always)]
+    ///Returns nonce used to seal message
+    pub const fn nonce(&self) -> &[u8; NONCE_SIZE] {
+        &self.nonce
+    }
+
+    #[inline(always)]
+    ///Returns ciphertext with tag appended
+    pub fn ciphertext(&self) -> &[u8] {
+        self.ciphertext.data()
+    }
+
+    #[inline(always)]
+    ///Returns size of serialized message
+    pub const fn serialized_len(&self) -> usize {
+        SEALED_HEADER_SIZE + self.ciphertext.len
+    }
+
+    ///Serializes message into `out`, returning number of bytes written
+    ///
+    ///Fails if `out` is smaller than [serialized_len](#method.serialized_len)
+    pub fn serialize(&self, out: &mut [u8]) -> Result<usize, aes_gcm::Error> {
+        let len = self.serialized_len();
+        if out.len() < len {
+            return Err(aes_gcm::Error);
+        }
+
+        out[0] = self.version;
+        out[1..SEALED_HEADER_SIZE].copy_from_slice(&self.nonce);
+        out[SEALED_HEADER_SIZE..len].copy_from_slice(self.ciphertext.data());
+        Ok(len)
+    }
+
+    ///Parses message from `input`, previously written by [serialize](#method.serialize)
+    ///
+    ///Fails if `input` is too short to hold header and tag, or ciphertext exceeds `N`.
+    ///Authenticity is only verified by [open](#method.open)
+    pub fn parse(input: &[u8]) -> Result<Self, aes_gcm::Error> {
+        if input.len() < SEALED_HEADER_SIZE + TAG_SIZE || input.len() - SEALED_HEADER_SIZE > N {
+            return Err(aes_gcm::Error);
+        }
+
+        let mut nonce = [0; NONCE_SIZE];
+        nonce.copy_from_slice(&input[1..SEALED_HEADER_SIZE]);
+        let ciphertext = &input[SEALED_HEADER_SIZE..];
+        let mut result = Self {
+            version: input[0],
+            nonce,
+            ciphertext: Buffer::new(),
+        };
+        result.ciphertext.data[..ciphertext.len()].copy_from_slice(ciphertext);
+        result.ciphertext.len = ciphertext.len();
+        Ok(result)
+    }
+}
+
+#[cfg(feature = "hash")]
+///Size of key commitment prepended by [CommittingCrypto](struct.CommittingCrypto.html)
+pub const COMMITMENT_SIZE: usize = 32;
+
+#[cfg(feature = "hash")]
+///Calculates buffer size to hold `size` data encrypted by [CommittingCrypto](struct.CommittingCrypto.html)
+///
+///It adds size of key commitment and AEAD tag
+pub const fn required_committing_buffer_size(size: usize) -> usize {
+    COMMITMENT_SIZE + required_buffer_size(size)
+}
+
+#[cfg(feature = "hash")]
+///Key committing AES-256 wrapper
+///
+///AES-GCM alone is not key committing: it is possible to craft ciphertext that successfully decrypts
+///under multiple keys. To prevent it, ciphertext is prefixed with commitment
+///`SHA-256(SHA-256(domain || key) || nonce)`, that is verified before decryption and is authenticated as associated data.
+///
+///Layout of encrypted buffer is `commitment || ciphertext || tag`, hence buffer's capacity should be
+///calculated using [required_committing_buffer_size](fn.required_committing_buffer_size.html)
+///
+///On drop key schedule and key digest are zeroed
+pub struct CommittingCrypto {
+    crypto: Crypto,
+    key_digest: [u8; COMMITMENT_SIZE],
+}
+
+#[cfg(feature = "hash")]
+impl CommittingCrypto {
+    const DOMAIN: &[u8] = b"obfus-key-commitment-v1";
+
     ///Creates new instance using provided key
     pub fn new(key: [u8; 32]) -> Self {
-        use aes_gcm::KeyInit;
+        use sha2::Digest;
+
+        let mut hasher = sha2::Sha256::new();
+        hasher.update(Self::DOMAIN);
+        hasher.update(key);
 
         Self {
-            aes: Aes256Gcm::new(&(key.into()))
+            crypto: Crypto::new(key),
+            key_digest: hasher.finalize().into(),
         }
     }
 
-    #[inline]
-    ///Encrypts content inside `buffer`
+    fn commitment(&self, nonce: &[u8; NONCE_SIZE]) -> [u8; COMMITMENT_SIZE] {
+        use sha2::Digest;
+
+        let mut hasher = sha2::Sha256::new();
+        hasher.update(self.key_digest);
+        hasher.update(nonce);
+        hasher.finalize().into()
+    }
+
+    ///Returns whether `in_out` is committed to this instance's key and `nonce`
     ///
-    ///Note that buffer's capacity should be calculated using [required_buffer_size](fn.required_buffer_size.html)
-    pub fn encrypt<const N: usize>(&self, nonce: [u8; NONCE_SIZE], in_out: &mut Buffer<N>) -> Result<(), aes_gcm::Error> {
+    ///Comparison is performed in constant time
+    pub fn verify_commitment<const N: usize>(&self, nonce: [u8; NONCE_SIZE], in_out: &Buffer<N>) -> bool {
+        if in_out.len < COMMITMENT_SIZE {
+            return false;
+        }
+
+        utils::secure_compare(&in_out.data[..COMMITMENT_SIZE], &self.commitment(&nonce))
+    }
+
+    ///Encrypts content inside `buffer`, prepending key commitment
+    ///
+    ///Fails if buffer has no capacity for commitment and tag
+    pub fn encrypt_committing<const N: usize>(&self, nonce: [u8; NONCE_SIZE], in_out: &mut Buffer<N>) -> Result<(), aes_gcm::Error> {
         use aes_gcm::AeadInOut;
 
-        self.aes.encrypt_in_place(&(nonce.into()), &[], in_out)
+        let len = in_out.len;
+        if N < COMMITMENT_SIZE + TAG_SIZE || len > N - COMMITMENT_SIZE - TAG_SIZE {
+            return Err(aes_gcm::Error);
+        }
+
+        let commitment = self.commitment(&nonce);
+        in_out.data.copy_within(..len, COMMITMENT_SIZE);
+        in_out.data[..COMMITMENT_SIZE].copy_from_slice(&commitment);
+
+        let (commitment, data) = in_out.data.split_at_mut(COMMITMENT_SIZE);
+        let data = &mut data[..len + TAG_SIZE];
+        let (data, tag) = data.split_at_mut(len);
+        match self.crypto.cipher.encrypt_inout_detached(&(nonce.into()), commitment, data.into()) {
+            Ok(result) => {
+                tag.copy_from_slice(&result);
+                in_out.len = COMMITMENT_SIZE + len + TAG_SIZE;
+                Ok(())
+            },
+            Err(error) => {
+                //Restore original content
+                in_out.data.copy_within(COMMITMENT_SIZE..COMMITMENT_SIZE + len, 0);
+                utils::secure_zero(&mut in_out.data[len..]);
+                Err(error)
+            }
+        }
     }
 
-    #[inline]
-    ///Decrypts content inside `buffer`
+    ///Decrypts content inside `buffer`, verifying key commitment first
     ///
-    ///Note that buffer's capacity should be calculated using [required_buffer_size](fn.required_buffer_size.html)
+    ///Fails if commitment does not match key and `nonce`, or if ciphertext is not authentic.
     ///
     ///On success `in_out` length will be truncated to the size of original data
-    pub fn decrypt<const N: usize>(&self, nonce: [u8; NONCE_SIZE], in_out: &mut Buffer<N>) -> Result<(), aes_gcm::Error> {
+    pub fn decrypt_committing<const N: usize>(&self, nonce: [u8; NONCE_SIZE], in_out: &mut Buffer<N>) -> Result<(), aes_gcm::Error> {
         use aes_gcm::AeadInOut;
 
-        self.aes.decrypt_in_place(&(nonce.into()), &[], in_out)
+        if N < COMMITMENT_SIZE + TAG_SIZE || in_out.len < COMMITMENT_SIZE + TAG_SIZE || !self.verify_commitment(nonce, in_out) {
+            return Err(aes_gcm::Error);
+        }
+
+        let len = in_out.len - COMMITMENT_SIZE - TAG_SIZE;
+        let (commitment, data) = in_out.data.split_at_mut(COMMITMENT_SIZE);
+        let (data, tag) = data[..len + TAG_SIZE].split_at_mut(len);
+        let mut tag_buf = [0; TAG_SIZE];
+        tag_buf.copy_from_slice(tag);
+        self.crypto.cipher.decrypt_inout_detached(&(nonce.into()), commitment, data.into(), &(tag_buf.into()))?;
+
+        in_out.data.copy_within(COMMITMENT_SIZE..COMMITMENT_SIZE + len, 0);
+        utils::secure_zero(&mut in_out.data[len..in_out.len]);
+        in_out.len = len;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "hash")]
+impl Drop for CommittingCrypto {
+    #[inline(always)]
+    fn drop(&mut self) {
+        utils::secure_zero(&mut self.key_digest);
+    }
+}
+
+#[cfg(feature = "chacha20poly1305")]
+///ChaCha20-Poly1305 wrapper
+///
+///Preferable on targets without AES hardware acceleration.
+///
+///Uses the same 16 bytes tag, hence buffer size is still calculated via [required_buffer_size](fn.required_buffer_size.html).
+///Nonce is 12 bytes, same as with [Crypto](struct.Crypto.html)
+///
+///On drop key is zeroed
+pub struct ChaChaCrypto {
+    cipher: chacha20poly1305::ChaCha20Poly1305
+}
+
+#[cfg(feature = "chacha20poly1305")]
+impl_aead!(ChaChaCrypto, chacha20poly1305::ChaCha20Poly1305, 32, 12, 16);
+
+#[cfg(feature = "chacha20poly1305")]
+///XChaCha20-Poly1305 wrapper
+///
+///Uses extended 24 bytes nonce, which is large enough to be generated randomly without risk of collision.
+///
+///Uses the same 16 bytes tag, hence buffer size is still calculated via [required_buffer_size](fn.required_buffer_size.html).
+///
+///On drop key is zeroed
+pub struct XChaChaCrypto {
+    cipher: chacha20poly1305::XChaCha20Poly1305
+}
+
+#[cfg(feature = "chacha20poly1305")]
+impl_aead!(XChaChaCrypto, chacha20poly1305::XChaCha20Poly1305, 32, 24, 16);
+
+#[cfg(feature = "siv")]
+///AES-256-GCM-SIV wrapper
+///
+///Unlike [Crypto](struct.Crypto.html) it is resistant to nonce misuse: re-using nonce only
+///reveals whether the same plaintext was encrypted, at the cost of being slower.
+///
+///Uses the same 16 bytes tag and 12 bytes nonce, hence buffer size is still calculated via [required_buffer_size](fn.required_buffer_size.html).
+///
+///On drop key schedule is zeroed
+pub struct SivCrypto {
+    cipher: aes_gcm_siv::Aes256GcmSiv
+}
+
+#[cfg(feature = "siv")]
+impl_aead!(SivCrypto, aes_gcm_siv::Aes256GcmSiv, 32, 12, 16);
+
+///Stream cipher using [Squares](../prng/struct.Squares.html) as keystream XORed into data
+///
+///**IMPORTANT**: it provides NO authentication and only weak confidentiality, as Squares is not
+///cryptographically secure. It is intended for obfuscation, use [Crypto](struct.Crypto.html) for security.
+///
+///`key` is used as Squares key, hence it should pass [is_weak_key](../prng/struct.Squares.html#method.is_weak_key) check,
+///while `nonce` selects keystream starting at counter `squares(key, nonce)`, so every bit of nonce affects it.
+///As starting counters are pseudo-random, two streams processing `L` words each overlap with
+///probability about `2L / 2^64`, which is negligible for practical lengths, but not impossible.
+///
+///Decryption is performed by applying keystream again, using new instance with the same `key` and `nonce`.
+pub struct SquaresStreamCipher {
+    prng: prng::Squares,
+    word: [u8; 8],
+    word_idx: usize,
+}
+
+impl SquaresStreamCipher {
+    #[inline]
+    ///Creates new instance with keystream selected by `key` and `nonce`
+    pub const fn new(key: u64, nonce: u64) -> Self {
+        Self {
+            prng: prng::Squares::with_key(key, prng::squares(key, nonce)),
+            word: [0; 8],
+            word_idx: 8,
+        }
+    }
+
+    ///XORs keystream into `data`, advancing it
+    ///
+    ///Consecutive calls continue keystream, hence splitting data across multiple calls produces
+    ///the same output as single call.
+    pub const fn apply(&mut self, data: &mut [u8]) {
+        let len = data.len();
+        let mut idx = 0;
+
+        while idx < len {
+            if self.word_idx == self.word.len() {
+                self.word = self.prng.next().to_le_bytes();
+                self.word_idx = 0;
+            }
+
+            data[idx] ^= self.word[self.word_idx];
+            self.word_idx += 1;
+            idx += 1;
+        }
+    }
+}
+
+impl Drop for SquaresStreamCipher {
+    #[inline]
+    fn drop(&mut self) {
+        utils::secure_zero(&mut self.word);
+        //Key is sensitive, as it allows to reproduce keystream
+        unsafe {
+            core::ptr::write_volatile(&mut self.prng, prng::Squares::with_key(0, 0));
+        }
+        utils::secure_fence();
     }
 }
@@ -7,14 +7,140 @@
 #![warn(missing_docs)]
 #![allow(clippy::style)]
 
-use core::marker;
+#[cfg(feature = "std")]
+extern crate std;
+
+use core::{fmt, marker, ops, sync};
 
 pub mod crypto;
+#[cfg(feature = "mlock")]
+pub mod locked;
+pub mod pipeline;
 pub mod prng;
 pub mod shuffle;
+pub mod storage;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod utils;
+pub mod xor;
 
 pub use aes_gcm;
+#[cfg(feature = "chacha20poly1305")]
+pub use chacha20poly1305;
+#[cfg(feature = "siv")]
+pub use aes_gcm_siv;
+#[cfg(feature = "zeroize")]
+pub use zeroize;
+#[cfg(feature = "os_rng")]
+pub use getrandom;
+#[cfg(feature = "rand_core")]
+pub use rand_core;
+#[cfg(feature = "subtle")]
+pub use subtle;
+#[cfg(feature = "serde")]
+pub use serde;
+#[cfg(feature = "hash")]
+pub use sha2;
+#[cfg(feature = "hkdf")]
+pub use hkdf;
+#[cfg(feature = "rayon")]
+pub use rayon;
+#[cfg(feature = "macros")]
+pub use obfus_macros::{obfuscate, secret_from_env};
+
+#[macro_export]
+///Creates [Secret](struct.Secret.html) of `Text` type from string, inferring its length
+///
+///Length is number of utf-8 bytes, not characters.
+///
+///Note that string is stored as it is within binary, use `obfuscate!` to hide it.
+///
+///Usage: `secret_text!("hello")`
+macro_rules! secret_text {
+    ($text:expr $(,)?) => {{
+        const TEXT: &str = $text;
+        const LEN: usize = TEXT.len();
+        const DATA: [u8; LEN] = {
+            let text = TEXT.as_bytes();
+            let mut data = [0u8; LEN];
+            let mut idx = 0;
+            while idx < LEN {
+                data[idx] = text[idx];
+                idx += 1;
+            }
+            data
+        };
+        const {
+            assert!(::core::str::from_utf8(&DATA).is_ok(), "secret_text!: text must be valid utf-8");
+        }
+
+        unsafe {
+            $crate::Secret::<LEN, $crate::Text>::new(DATA)
+        }
+    }};
+}
+
+#[macro_export]
+///Shuffles byte string with [FisherYates](shuffle/struct.FisherYates.html) using `seed`, inferring its length
+///
+///Expands to `[u8; N]` expression, which is always evaluated at compile time, even within `let` binding.
+///
+///Note that plaintext is still present in source code, but only shuffled form is stored within binary.
+///
+///Both `seed` and byte string must be constants, as they are evaluated within `const` block.
+///
+///Usage: `shuffled_const!(seed = 1, b"secret")`
+macro_rules! shuffled_const {
+    (seed = $seed:expr, $bytes:expr $(,)?) => {
+        const {
+            $crate::shuffle::FisherYates::with_seed($seed).shuffle_const(*$bytes)
+        }
+    };
+}
+
+#[macro_export]
+///Explicitly reveals content of [Secret](struct.Secret.html)
+///
+///Evaluates to `&str` for `Text` secret and to `&[u8]` for `Binary` secret.
+///
+///**IMPORTANT**: revealed content is plaintext, it is user's responsibility to not let it leak (e.g. into logs).
+///`Secret` deliberately does not implement `Display`, so that every exposure is explicit and can be found by searching for `reveal!`.
+///
+///Usage: `reveal!(secret)`
+macro_rules! reveal {
+    ($secret:expr $(,)?) => {
+        $crate::__private::Reveal::reveal(&$secret)
+    };
+}
+
+#[doc(hidden)]
+pub mod __private {
+    use super::{Secret, Binary, Text};
+
+    pub trait Reveal {
+        type Output: ?Sized;
+
+        fn reveal(&self) -> &Self::Output;
+    }
+
+    impl<const N: usize> Reveal for Secret<N, Text> {
+        type Output = str;
+
+        #[inline(always)]
+        fn reveal(&self) -> &Self::Output {
+            self.as_str()
+        }
+    }
+
+    impl<const N: usize> Reveal for Secret<N, Binary> {
+        type Output = [u8];
+
+        #[inline(always)]
+        fn reveal(&self) -> &Self::Output {
+            self.data()
+        }
+    }
+}
 
 mod seal {
     pub trait Seal {}
@@ -46,11 +172,198 @@ pub struct Secret<const N: usize, TAG: SecretType> {
 }
 
 impl<const N: usize, T: SecretType> Secret<N, T> {
+    #[inline(always)]
+    ///Returns size of secret
+    pub const fn len(&self) -> usize {
+        N
+    }
+
+    #[inline(always)]
+    ///Returns whether secret is empty
+    pub const fn is_empty(&self) -> bool {
+        N == 0
+    }
+
     #[inline(always)]
     ///Access raw data
     pub fn data(&self) -> &[u8] {
         self.data.as_slice()
     }
+
+    #[inline(always)]
+    ///Returns iterator over copies of secret's bytes
+    pub fn iter(&self) -> impl Iterator<Item = u8> + '_ {
+        self.data.iter().copied()
+    }
+
+    #[inline(always)]
+    ///Provides access to secret's content within scope of `cb`
+    ///
+    ///Secret remains zeroed on drop, but it is user's responsibility to not let closure leak secret's content outside.
+    pub fn expose<R, F: FnOnce(&[u8]) -> R>(&self, cb: F) -> R {
+        cb(self.data())
+    }
+
+    #[inline]
+    ///Converts secret into guard, exposing secret's content until it is dropped
+    ///
+    ///Guard holds scratch copy of content, that is zeroed on drop.
+    ///
+    ///Note that it cannot prevent compiler from leaving copies on stack when guard is moved, nor it can
+    ///prevent user from copying content out.
+    pub fn into_exposed(self) -> Exposed<N> {
+        let mut result = Exposed {
+            data: [0; N],
+        };
+        result.data.copy_from_slice(self.data());
+        result
+    }
+
+    #[inline]
+    ///Transforms secret's content into new binary secret
+    ///
+    ///Closure is given read-only view of the secret and its output is stored directly within new
+    ///[Secret](struct.Secret.html).
+    ///
+    ///Note that it is user's responsibility to not let closure leak secret's content outside.
+    pub fn map<const M: usize, F: FnOnce(&[u8]) -> [u8; M]>(&self, cb: F) -> Secret<M, Binary> {
+        Secret::<M, Binary>::new(cb(self.data()))
+    }
+
+    #[cfg(feature = "hkdf")]
+    ///Derives new binary secret of size `M` from this secret using HKDF-SHA256
+    ///
+    ///Different `info` labels produce independent secrets, allowing to derive multiple keys out of
+    ///single master secret.
+    ///
+    ///Note that master secret must have sufficient entropy (e.g. random key), as HKDF is not suitable
+    ///to stretch passwords.
+    ///
+    ///`M` must not exceed `255 * 32`, which is checked at compile time.
+    pub fn hkdf_expand<const M: usize>(&self, salt: &[u8], info: &[u8]) -> Secret<M, Binary> {
+        const {
+            assert!(M <= 255 * 32, "Secret::hkdf_expand: M must not exceed 255 * 32");
+        }
+
+        let hkdf = hkdf::Hkdf::<sha2::Sha256>::new(Some(salt), self.data());
+        let mut result = Secret::<M, Binary>::new([0; M]);
+        match hkdf.expand(info, &mut result.data) {
+            Ok(()) => result,
+            Err(_) => unreachable!(),
+        }
+    }
+
+    #[inline]
+    ///XORs secret with `other` secret byte-wise in constant time, returning result as new secret
+    ///
+    ///Result is always `Binary`, as XOR of text is not text in general.
+    pub fn xor<O: SecretType>(&self, other: &Secret<N, O>) -> Secret<N, Binary> {
+        let mut result = Secret::<N, Binary>::new([0; N]);
+        for idx in 0..N {
+            result.data[idx] = self.data[idx] ^ other.data[idx];
+        }
+        result
+    }
+
+    #[inline]
+    ///Splits secret into two XOR shares, using [Squares](prng/struct.Squares.html) seeded with `rng_seed` as randomness
+    ///
+    ///Returns `(random_share, secret ^ random_share)`, neither of which reveals secret alone.
+    ///Use [combine_shares](#method.combine_shares) to recover secret.
+    ///
+    ///**WARNING**: `Squares` is not cryptographically secure and anyone knowing `rng_seed` can
+    ///reproduce random share, hence it only provides obfuscation. Prefer `split_shares_random` (requires `os_rng` feature).
+    ///
+    ///Original secret is zeroed once split is done.
+    pub fn split_shares(self, rng_seed: u64) -> (Secret<N, Binary>, Secret<N, Binary>) {
+        let mut random_share = Secret::<N, Binary>::new([0; N]);
+        prng::Squares::new(rng_seed).fill_bytes(&mut random_share.data);
+        let share = self.xor(&random_share);
+        (random_share, share)
+    }
+
+    #[cfg(feature = "os_rng")]
+    #[inline]
+    ///Splits secret into two XOR shares, using OS random number generator as randomness
+    ///
+    ///Returns `(random_share, secret ^ random_share)`, neither of which reveals secret alone.
+    ///Use [combine_shares](#method.combine_shares) to recover secret.
+    ///
+    ///Original secret is zeroed once split is done, even if OS is unable to provide entropy.
+    pub fn split_shares_random(self) -> Result<(Secret<N, Binary>, Secret<N, Binary>), getrandom::Error> {
+        let mut random_share = Secret::<N, Binary>::new([0; N]);
+        getrandom::fill(&mut random_share.data)?;
+        let share = self.xor(&random_share);
+        Ok((random_share, share))
+    }
+
+    #[inline]
+    ///Splits secret into two binary secrets of size `L` and `R`
+    ///
+    ///`L + R` must be equal to `N`, which is checked at compile time.
+    ///
+    ///Original secret is zeroed once split is done.
+    pub fn split_at<const L: usize, const R: usize>(self) -> (Secret<L, Binary>, Secret<R, Binary>) {
+        const {
+            assert!(L + R == N, "Secret::split_at: L + R must be equal to N");
+        }
+
+        let mut left = Secret::<L, Binary>::new([0; L]);
+        let mut right = Secret::<R, Binary>::new([0; R]);
+        left.data.copy_from_slice(&self.data[..L]);
+        right.data.copy_from_slice(&self.data[L..]);
+        (left, right)
+    }
+
+    #[inline(always)]
+    ///Compares secret with `other` in constant time
+    ///
+    ///Execution time depends only on `N`, except when `other` length differs from `N` in which
+    ///case it returns `false` immediately
+    pub fn ct_eq(&self, other: &[u8]) -> bool {
+        utils::secure_compare(self.data(), other)
+    }
+
+    #[inline(always)]
+    ///Compares secret with `other` secret in constant time
+    ///
+    ///Refer to [ct_eq](#method.ct_eq) for details
+    pub fn ct_eq_secret<const M: usize, O: SecretType>(&self, other: &Secret<M, O>) -> bool {
+        self.ct_eq(other.data())
+    }
+
+    #[inline(always)]
+    ///Selects `a` when `choice` is `false` or `b` when `choice` is `true`, in constant time
+    ///
+    ///Choice is turned into byte mask which is applied across every byte of both secrets, so no
+    ///branching depends on `choice`.
+    pub fn conditional_select(a: &Self, b: &Self, choice: bool) -> Self {
+        Self::select_masked(a, b, choice as u8)
+    }
+
+    #[cfg(feature = "subtle")]
+    #[inline(always)]
+    ///Selects `a` when `choice` is `0` or `b` when `choice` is `1`, in constant time
+    ///
+    ///Refer to [conditional_select](#method.conditional_select) for details
+    pub fn conditional_select_choice(a: &Self, b: &Self, choice: subtle::Choice) -> Self {
+        Self::select_masked(a, b, choice.unwrap_u8())
+    }
+
+    fn select_masked(a: &Self, b: &Self, choice: u8) -> Self {
+        //Volatile read to prevent compiler from turning mask back into branch
+        let mask = utils::secure_read(&choice).wrapping_neg();
+
+        let mut data = [0; N];
+        for idx in 0..N {
+            data[idx] = a.data[idx] ^ (mask & (a.data[idx] ^ b.data[idx]));
+        }
+
+        Self {
+            data,
+            _tag: marker::PhantomData
+        }
+    }
 }
 
 impl<const N: usize> Secret<N, Binary> {
@@ -63,6 +376,69 @@ impl<const N: usize> Secret<N, Binary> {
         }
 
     }
+
+    #[inline(always)]
+    ///Creates new instance, taking ownership of `data`
+    ///
+    ///Secret becomes responsible for zeroing `data` on drop, but note that array passed by value
+    ///may still be copied by compiler, use [take_from](#method.take_from) to zero original location.
+    pub const fn from_array(data: [u8; N]) -> Self {
+        Self::new(data)
+    }
+
+    #[inline]
+    ///Creates new instance by copying content of `src`, zeroing `src` afterwards
+    ///
+    ///Ensures that original location does not retain plaintext copy.
+    pub fn take_from(src: &mut [u8; N]) -> Self {
+        let result = Self::new(*src);
+        utils::secure_zero(src);
+        result
+    }
+
+    #[inline]
+    ///Combines XOR shares produced by [split_shares](#method.split_shares), recovering original secret
+    ///
+    ///Both shares are zeroed once combined.
+    pub fn combine_shares(left: Self, right: Self) -> Self {
+        left.xor(&right)
+    }
+
+    #[inline]
+    ///Creates new instance by concatenating `left` and `right` secrets
+    ///
+    ///`L + R` must be equal to `N`, which is checked at compile time.
+    ///
+    ///Both secrets are zeroed once concatenation is done.
+    pub fn concat<const L: usize, const R: usize, LT: SecretType, RT: SecretType>(left: Secret<L, LT>, right: Secret<R, RT>) -> Self {
+        const {
+            assert!(L + R == N, "Secret::concat: L + R must be equal to N");
+        }
+
+        let mut result = Self::new([0; N]);
+        result.data[..L].copy_from_slice(left.data());
+        result.data[L..].copy_from_slice(right.data());
+        result
+    }
+
+    #[inline]
+    ///Converts secret into `Text` secret, if content is valid utf-8
+    ///
+    ///Content is copied directly into new secret, while original is zeroed.
+    ///
+    ///On failure original secret is returned as it is.
+    pub fn into_text(self) -> Result<Secret<N, Text>, Self> {
+        if core::str::from_utf8(self.data()).is_err() {
+            return Err(self);
+        }
+
+        let mut result = Secret::<N, Text> {
+            data: [0; N],
+            _tag: marker::PhantomData
+        };
+        result.data.copy_from_slice(self.data());
+        Ok(result)
+    }
 }
 
 
@@ -96,6 +472,71 @@ impl<const N: usize> Secret<N, Text> {
             core::str::from_utf8_unchecked(self.data())
         }
     }
+
+    #[inline]
+    ///Access secret as string, validating that content is utf-8
+    ///
+    ///Unlike [as_str](#method.as_str) it does not rely on content being validated on construction.
+    pub fn try_as_str(&self) -> Result<&str, core::str::Utf8Error> {
+        core::str::from_utf8(self.data())
+    }
+
+    ///Compares secret with `other` in constant time, ignoring ASCII case
+    ///
+    ///Only ASCII characters are case folded, while the rest is compared as it is.
+    ///
+    ///Returns `false` immediately if `other` length differs from `N`
+    pub fn ct_eq_ignore_ascii_case(&self, other: &str) -> bool {
+        let other = other.as_bytes();
+        if other.len() != N {
+            return false;
+        }
+
+        let mut acc = 0u8;
+        for idx in 0..N {
+            acc |= self.data[idx].to_ascii_lowercase() ^ other[idx].to_ascii_lowercase();
+        }
+
+        sync::atomic::compiler_fence(sync::atomic::Ordering::SeqCst);
+        acc == 0
+    }
+
+    #[inline(always)]
+    ///Returns iterator over secret's characters
+    pub fn chars(&self) -> core::str::Chars<'_> {
+        self.as_str().chars()
+    }
+
+    ///Writes secret into `out`, revealing only first `visible` characters, while every following character is replaced with `mask`
+    ///
+    ///**IMPORTANT**: it intentionally exposes part of the secret, so it should only be used to display
+    ///hints (e.g. `sk-ab****`) and `visible` should be kept small.
+    ///
+    ///Operates on characters, hence multibyte sequences are never split.
+    ///
+    ///Returns `None` if `out` is not large enough, leaving its content unspecified.
+    pub fn prefix_masked<'a>(&self, visible: usize, mask: char, out: &'a mut [u8]) -> Option<&'a str> {
+        let text = self.as_str();
+        let visible_len = match text.char_indices().nth(visible) {
+            Some((idx, _)) => idx,
+            None => text.len(),
+        };
+        let masked_count = text[visible_len..].chars().count();
+
+        let mut mask_buf = [0u8; 4];
+        let mask = mask.encode_utf8(&mut mask_buf).as_bytes();
+        let len = masked_count.checked_mul(mask.len())?.checked_add(visible_len)?;
+        if out.len() < len {
+            return None;
+        }
+
+        out[..visible_len].copy_from_slice(&self.data[..visible_len]);
+        for chunk in out[visible_len..len].chunks_exact_mut(mask.len()) {
+            chunk.copy_from_slice(mask);
+        }
+
+        core::str::from_utf8(&out[..len]).ok()
+    }
 }
 
 impl<const N: usize, T: SecretType> AsRef<[u8]> for Secret<N, T> {
@@ -112,10 +553,332 @@ impl<const N: usize> AsRef<str> for Secret<N, Text> {
     }
 }
 
+impl<const N: usize> From<[u8; N]> for Secret<N, Binary> {
+    #[inline(always)]
+    fn from(data: [u8; N]) -> Self {
+        Self::new(data)
+    }
+}
+
+impl<const N: usize> TryFrom<[u8; N]> for Secret<N, Text> {
+    type Error = core::str::Utf8Error;
+
+    #[inline]
+    fn try_from(mut data: [u8; N]) -> Result<Self, Self::Error> {
+        match core::str::from_utf8(&data) {
+            Ok(_) => Ok(unsafe { Self::new(data) }),
+            Err(error) => {
+                utils::secure_zero(&mut data);
+                Err(error)
+            }
+        }
+    }
+}
+
+//Conversion of secret into array is not implemented on purpose, as it would leave copy of secret
+//that is not zeroed on drop. Use reveal! or data() to access content explicitly
+
+impl<const N: usize, T: SecretType> fmt::Debug for Secret<N, T> {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "Secret<{N}>([REDACTED])")
+    }
+}
+
+///Compares secrets in constant time, unlike derived implementations
+///
+///Every byte is compared regardless of where mismatch happens, so it is safe to derive `PartialEq`
+///on types containing secret
+impl<const N: usize, T: SecretType> PartialEq for Secret<N, T> {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other.data())
+    }
+}
+
+impl<const N: usize, T: SecretType> Eq for Secret<N, T> {}
+
+#[cfg(feature = "std")]
+//Per process key, so that hash of secret cannot be pre-computed
+fn hash_key() -> &'static [u8; 32] {
+    static KEY: std::sync::OnceLock<[u8; 32]> = std::sync::OnceLock::new();
+    KEY.get_or_init(|| {
+        let mut key = [0u8; 32];
+        getrandom::fill(&mut key).expect("OS random number generator to be available");
+        key
+    })
+}
+
+#[cfg(feature = "std")]
+///Hashes `SHA-256(key || secret)` using random per process key, instead of secret itself
+///
+///This is best-effort measure: standard `HashMap` hashers are not designed to protect secrets and
+///map still reveals which secrets are equal. The digest only prevents hash values from trivially
+///revealing content.
+///
+///Panics if OS random number generator is not available on first use
+impl<const N: usize, T: SecretType> core::hash::Hash for Secret<N, T> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        use sha2::Digest;
+
+        let mut hasher = sha2::Sha256::new();
+        hasher.update(hash_key());
+        hasher.update(self.data());
+        let mut digest: [u8; 32] = hasher.finalize().into();
+        state.write(&digest);
+        utils::secure_zero(&mut digest);
+    }
+}
+
+//Display is not implemented on purpose, so that secret cannot be formatted accidentally.
+//Use reveal! to access content explicitly
+
 impl<const N: usize, T: SecretType> Drop for Secret<N, T> {
     #[inline(always)]
     fn drop(&mut self) {
-        utils::secure_memset(&mut self.data, 0);
+        utils::secure_zero(&mut self.data);
+        #[cfg(feature = "testing")]
+        testing::record_secret_wipe();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<const N: usize, T: SecretType> zeroize::Zeroize for Secret<N, T> {
+    #[inline(always)]
+    fn zeroize(&mut self) {
+        utils::secure_zero(&mut self.data);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<const N: usize, T: SecretType> zeroize::ZeroizeOnDrop for Secret<N, T> {}
+
+#[cfg(feature = "subtle")]
+impl<const N: usize, T: SecretType> subtle::ConstantTimeEq for Secret<N, T> {
+    #[inline(always)]
+    fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        self.data.ct_eq(&other.data)
+    }
+}
+
+///Secret storage with runtime length, limited by `MAX`
+///
+///On drop whole `MAX` bytes are zeroed
+pub struct VarSecret<const MAX: usize, TAG: SecretType> {
+    data: [u8; MAX],
+    len: usize,
+    _tag: marker::PhantomData<TAG>
+}
+
+impl<const MAX: usize, T: SecretType> VarSecret<MAX, T> {
+    #[inline(always)]
+    const fn from_slice(data: &[u8]) -> Option<Self> {
+        if data.len() > MAX {
+            return None;
+        }
+
+        let mut result = Self {
+            data: [0; MAX],
+            len: data.len(),
+            _tag: marker::PhantomData
+        };
+        let mut idx = 0;
+        while idx < data.len() {
+            result.data[idx] = data[idx];
+            idx += 1;
+        }
+        Some(result)
+    }
+
+    #[inline(always)]
+    ///Returns size of secret
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline(always)]
+    ///Returns whether secret is empty
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline(always)]
+    ///Returns maximum size of secret
+    pub const fn capacity(&self) -> usize {
+        MAX
+    }
+
+    #[inline(always)]
+    ///Access raw data
+    pub fn data(&self) -> &[u8] {
+        &self.data[..self.len]
+    }
+
+    #[inline(always)]
+    ///Compares secret with `other` in constant time
+    ///
+    ///Refer to [Secret::ct_eq](struct.Secret.html#method.ct_eq) for details
+    pub fn ct_eq(&self, other: &[u8]) -> bool {
+        utils::secure_compare(self.data(), other)
+    }
+}
+
+impl<const MAX: usize> VarSecret<MAX, Binary> {
+    #[inline]
+    ///Creates new instance with copy of `data`, returning `None` if it exceeds `MAX`
+    pub const fn new(data: &[u8]) -> Option<Self> {
+        Self::from_slice(data)
+    }
+}
+
+impl<const MAX: usize> VarSecret<MAX, Text> {
+    #[inline]
+    ///Creates new instance with copy of `data`, returning `None` if it exceeds `MAX`
+    pub const fn new(data: &str) -> Option<Self> {
+        Self::from_slice(data.as_bytes())
+    }
+
+    #[inline(always)]
+    ///Access secret as string
+    pub fn as_str(&self) -> &str {
+        unsafe {
+            core::str::from_utf8_unchecked(self.data())
+        }
+    }
+}
+
+impl<const MAX: usize, T: SecretType> AsRef<[u8]> for VarSecret<MAX, T> {
+    #[inline(always)]
+    fn as_ref(&self) -> &[u8] {
+        self.data()
+    }
+}
+
+impl<const MAX: usize, T: SecretType> fmt::Debug for VarSecret<MAX, T> {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "VarSecret<{MAX}>([REDACTED])")
+    }
+}
+
+impl<const MAX: usize, T: SecretType> Drop for VarSecret<MAX, T> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        utils::secure_zero(&mut self.data);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<const MAX: usize, T: SecretType> zeroize::ZeroizeOnDrop for VarSecret<MAX, T> {}
+
+///Builder to construct [Secret](struct.Secret.html) incrementally
+///
+///On drop partially written data is zeroed
+pub struct SecretBuilder<const N: usize> {
+    data: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> SecretBuilder<N> {
+    #[inline(always)]
+    ///Creates new empty instance
+    pub const fn new() -> Self {
+        Self {
+            data: [0; N],
+            len: 0,
+        }
+    }
+
+    #[inline(always)]
+    ///Returns number of bytes written so far
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline(always)]
+    ///Returns whether nothing is written yet
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline(always)]
+    ///Returns number of bytes remaining to be written
+    pub const fn remaining(&self) -> usize {
+        N - self.len
+    }
+
+    ///Appends `data` to the secret
+    ///
+    ///Fails if `data` exceeds [remaining](#method.remaining) space, leaving nothing written
+    pub fn push_slice(&mut self, data: &[u8]) -> Result<(), ()> {
+        if data.len() > self.remaining() {
+            return Err(());
+        }
+
+        self.data[self.len..self.len + data.len()].copy_from_slice(data);
+        self.len += data.len();
+        Ok(())
+    }
+
+    #[inline]
+    ///Finishes construction, returning secret only if exactly `N` bytes were written
+    pub fn finish(self) -> Option<Secret<N, Binary>> {
+        if self.len == N {
+            Some(Secret::<N, Binary>::new(self.data))
+        } else {
+            None
+        }
+    }
+}
+
+impl<const N: usize> Default for SecretBuilder<N> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> fmt::Debug for SecretBuilder<N> {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "SecretBuilder<{N}>({}/{N} [REDACTED])", self.len)
+    }
+}
+
+impl<const N: usize> Drop for SecretBuilder<N> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        utils::secure_zero(&mut self.data);
+    }
+}
+
+///Guard exposing [Secret](struct.Secret.html) content
+///
+///On drop data is zeroed
+pub struct Exposed<const N: usize> {
+    data: [u8; N],
+}
+
+impl<const N: usize> ops::Deref for Exposed<N> {
+    type Target = [u8];
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        self.data.as_slice()
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for Exposed<N> {
+    #[inline(always)]
+    fn as_ref(&self) -> &[u8] {
+        self.data.as_slice()
+    }
+}
+
+impl<const N: usize> Drop for Exposed<N> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        utils::secure_zero(&mut self.data);
     }
 }
 
@@ -0,0 +1,142 @@
+//!Heap allocated secrets with locked memory
+//!
+//!Memory locking prevents OS from swapping pages containing secret to disk, but note that:
+//!
+//!- Locking is subject to OS limits (e.g. `RLIMIT_MEMLOCK` on Linux), hence it can fail.
+//!- Whole pages are locked, so other data sharing the same page is locked too, and unlocking
+//!releases lock of the whole page.
+//!- Locks are not reference counted, hence dropping one [LockedSecret](struct.LockedSecret.html)
+//!unlocks pages it shares with other `LockedSecret` instances, which then may be swapped to disk.
+//!Keep secrets that must stay locked alive for the same duration, or allocate them on separate pages.
+//!- It does not prevent secret from being written into core dumps or hibernation files.
+
+extern crate std;
+
+use crate::{utils, Secret, SecretType, Text};
+
+use core::marker;
+use std::boxed::Box;
+use std::io;
+
+#[cfg(unix)]
+fn lock(ptr: *const u8, len: usize) -> io::Result<()> {
+    match unsafe { libc::mlock(ptr.cast(), len) } {
+        0 => Ok(()),
+        _ => Err(io::Error::last_os_error()),
+    }
+}
+
+#[cfg(unix)]
+fn unlock(ptr: *const u8, len: usize) {
+    unsafe {
+        libc::munlock(ptr.cast(), len);
+    }
+}
+
+#[cfg(windows)]
+fn lock(ptr: *const u8, len: usize) -> io::Result<()> {
+    match unsafe { windows_sys::Win32::System::Memory::VirtualLock(ptr.cast(), len) } {
+        0 => Err(io::Error::last_os_error()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(windows)]
+fn unlock(ptr: *const u8, len: usize) {
+    unsafe {
+        windows_sys::Win32::System::Memory::VirtualUnlock(ptr.cast(), len);
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn lock(_: *const u8, _: usize) -> io::Result<()> {
+    Err(io::ErrorKind::Unsupported.into())
+}
+
+#[cfg(not(any(unix, windows)))]
+fn unlock(_: *const u8, _: usize) {
+}
+
+///Heap allocated [Secret](../struct.Secret.html) with memory locked in RAM
+///
+///On drop data is zeroed and memory is unlocked
+pub struct LockedSecret<const N: usize, TAG: SecretType> {
+    data: Box<[u8; N]>,
+    _tag: marker::PhantomData<TAG>
+}
+
+impl<const N: usize, T: SecretType> LockedSecret<N, T> {
+    ///Moves `secret` into locked memory
+    ///
+    ///Fails if OS cannot lock memory, in which case `secret` is dropped without being copied
+    pub fn new(secret: Secret<N, T>) -> io::Result<Self> {
+        let mut data = Box::<[u8; N]>::new_uninit();
+        let mut data = unsafe {
+            data.as_mut_ptr().write_bytes(0, 1);
+            data.assume_init()
+        };
+        //Lock before copying, so that secret is never in unlocked memory
+        if N > 0 {
+            lock(data.as_ptr(), N)?;
+        }
+
+        data.copy_from_slice(secret.data());
+
+        Ok(Self {
+            data,
+            _tag: marker::PhantomData
+        })
+    }
+
+    #[inline(always)]
+    ///Returns size of secret
+    pub const fn len(&self) -> usize {
+        N
+    }
+
+    #[inline(always)]
+    ///Returns whether secret is empty
+    pub const fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    #[inline(always)]
+    ///Access raw data
+    pub fn data(&self) -> &[u8] {
+        self.data.as_slice()
+    }
+}
+
+impl<const N: usize> LockedSecret<N, Text> {
+    #[inline(always)]
+    ///Access secret as string
+    pub fn as_str(&self) -> &str {
+        unsafe {
+            core::str::from_utf8_unchecked(self.data())
+        }
+    }
+}
+
+impl<const N: usize, T: SecretType> AsRef<[u8]> for LockedSecret<N, T> {
+    #[inline(always)]
+    fn as_ref(&self) -> &[u8] {
+        self.data()
+    }
+}
+
+impl<const N: usize, T: SecretType> core::fmt::Debug for LockedSecret<N, T> {
+    #[inline]
+    fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(fmt, "LockedSecret<{N}>([REDACTED])")
+    }
+}
+
+impl<const N: usize, T: SecretType> Drop for LockedSecret<N, T> {
+    #[inline]
+    fn drop(&mut self) {
+        utils::secure_zero(self.data.as_mut_slice());
+        if N > 0 {
+            unlock(self.data.as_ptr(), N);
+        }
+    }
+}
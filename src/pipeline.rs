@@ -0,0 +1,71 @@
+//!Compile time obfuscation pipeline
+//!
+//!AES cannot be evaluated in `const` context, hence secrets cannot be encrypted at compile time.
+//![Pipeline](struct.Pipeline.html) bridges this gap by combining shuffle and XOR masking, which
+//!are both `const`, producing reversible obfuscated constants.
+//!
+//!**IMPORTANT**: it is obfuscation, not encryption, as seed must be embedded within binary to
+//!reverse it. Use runtime [Crypto](../crypto/struct.Crypto.html) for real encryption.
+
+use crate::prng;
+use crate::shuffle::FisherYates;
+use crate::xor::XorCipher;
+
+///Obfuscation pipeline, shuffling data with [FisherYates](../shuffle/struct.FisherYates.html),
+///followed by XOR masking with [XorCipher](../xor/struct.XorCipher.html)
+///
+///Both stages use independent seeds derived from pipeline's seed.
+///
+///```rust
+///use obfus::pipeline::Pipeline;
+///
+///const PIPELINE: Pipeline = Pipeline::with_seed(0x1234);
+///const SECRET: [u8; 6] = PIPELINE.obfuscate_const(*b"secret");
+///
+///assert_ne!(&SECRET, b"secret");
+///assert_eq!(&PIPELINE.deobfuscate_const(SECRET), b"secret");
+///```
+pub struct Pipeline {
+    shuffle: FisherYates,
+    xor: XorCipher,
+}
+
+impl Pipeline {
+    #[inline]
+    ///Creates new instance with provided `seed`
+    pub const fn with_seed(seed: u64) -> Self {
+        let mut prng = prng::Squares::new(seed);
+        Self {
+            shuffle: FisherYates::with_seed(prng.next()),
+            xor: XorCipher::with_seed(prng.next()),
+        }
+    }
+
+    #[inline]
+    ///Performs obfuscation
+    pub const fn obfuscate<'a>(&self, in_out: &'a mut [u8]) -> &'a mut [u8] {
+        self.shuffle.shuffle(in_out);
+        self.xor.obfuscate(in_out)
+    }
+
+    #[inline(always)]
+    ///Performs obfuscation of constant array
+    pub const fn obfuscate_const<const N: usize>(&self, mut data: [u8; N]) -> [u8; N] {
+        self.obfuscate(&mut data);
+        data
+    }
+
+    #[inline]
+    ///Reverses [obfuscate](#method.obfuscate)
+    pub const fn deobfuscate<'a>(&self, in_out: &'a mut [u8]) -> &'a mut [u8] {
+        self.xor.deobfuscate(in_out);
+        self.shuffle.reverse(in_out)
+    }
+
+    #[inline(always)]
+    ///Reverses obfuscation of constant array
+    pub const fn deobfuscate_const<const N: usize>(&self, mut data: [u8; N]) -> [u8; N] {
+        self.deobfuscate(&mut data);
+        data
+    }
+}
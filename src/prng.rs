@@ -57,6 +57,88 @@ impl Squares {
         }
     }
 
+    ///Creates new instance using provided `key` and `seed`, if key passes basic quality check
+    ///
+    ///Refer to [is_weak_key](#method.is_weak_key) for what is considered weak key
+    pub const fn with_key_checked(key: u64, seed: u64) -> Option<Self> {
+        if Self::is_weak_key(key) {
+            None
+        } else {
+            Some(Self::with_key(key, seed))
+        }
+    }
+
+    ///Returns whether `key` fails basic quality heuristic
+    ///
+    ///Key is considered weak when number of ones bits is outside of `[24, 40]` range, or when either
+    ///of its 32 bits halves has number of ones bits outside of `[8, 24]` range.
+    ///
+    ///This only catches obviously bad keys, like `0`, `u64::MAX` or `0xFFFFFFFF`.
+    pub const fn is_weak_key(key: u64) -> bool {
+        let ones = key.count_ones();
+        let high = (key >> 32).count_ones();
+        let low = (key as u32).count_ones();
+
+        ones < 24 || ones > 40 || high < 8 || high > 24 || low < 8 || low > 24
+    }
+
+    #[inline(always)]
+    ///Returns current state as `(key, counter)`, where counter is seed of next number to generate
+    ///
+    ///Intended to checkpoint generator, in order to resume via [from_state](#method.from_state).
+    ///
+    ///**IMPORTANT**: key is sensitive, as it allows to reproduce every number, hence state must be stored securely.
+    pub const fn state(&self) -> (u64, u64) {
+        (self.key, self.seed)
+    }
+
+    #[inline(always)]
+    ///Restores generator from state returned by [state](#method.state)
+    pub const fn from_state(key: u64, counter: u64) -> Self {
+        Self::with_key(key, counter)
+    }
+
+    #[inline]
+    ///Derives independent substream identified by `stream_id`
+    ///
+    ///Substream uses the same key, with counter offset by `(stream_id + 1) * 2^32` from current
+    ///seed, hence different substreams and parent do not overlap as long as:
+    ///
+    ///- Each of them generates fewer than `2^32` numbers;
+    ///- `stream_id` is below `2^32 - 1`, as only lower 32 bits of `stream_id + 1` are distinguished.
+    ///
+    ///Note that substreams are only independent from each other as much as numbers produced by the same key.
+    pub const fn split(&self, stream_id: u64) -> Self {
+        Self::with_key(self.key, self.seed.wrapping_add(stream_id.wrapping_add(1) << 32))
+    }
+
+    #[inline(always)]
+    ///Resets seed, producing the same numbers as new instance with the same key and `seed`
+    pub const fn reseed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    #[inline(always)]
+    ///Replaces key, while retaining current seed
+    ///
+    ///Note that changing key in the middle of shuffle breaks its reverse-ability unless the same
+    ///change is replicated during reverse.
+    pub const fn rekey(&mut self, key: u64) {
+        self.key = key;
+    }
+
+    #[inline(always)]
+    ///Sets seed to `counter`, making it next number to generate
+    pub const fn jump_to(&mut self, counter: u64) {
+        self.seed = counter;
+    }
+
+    #[inline(always)]
+    ///Advances seed by `delta`, skipping `delta` numbers
+    pub const fn jump_ahead(&mut self, delta: u64) {
+        self.seed = self.seed.wrapping_add(delta);
+    }
+
     ///Generates new number, advancing seed
     pub const fn next(&mut self) -> u64 {
         let result = squares(self.key, self.seed);
@@ -64,6 +146,70 @@ impl Squares {
         result
     }
 
+    ///Generates number within range `[0, bound)` without modulo bias, advancing seed
+    ///
+    ///Uses rejection sampling, hence it may consume more than one number.
+    ///
+    ///`bound` must be non-zero
+    pub const fn gen_range(&mut self, bound: u64) -> u64 {
+        debug_assert!(bound > 0, "bound must be non-zero");
+        //2^64 mod bound, numbers below it would bias result
+        let threshold = bound.wrapping_neg() % bound;
+
+        loop {
+            let result = self.next();
+            if result >= threshold {
+                return result % bound;
+            }
+        }
+    }
+
+    #[inline(always)]
+    ///Generates number within range `[0, bound)` using plain modulo, advancing seed
+    ///
+    ///Always consumes exactly one number, but result is biased unless `bound` is power of two.
+    ///
+    ///`bound` must be non-zero
+    pub const fn gen_range_biased(&mut self, bound: u64) -> u64 {
+        self.next() % bound
+    }
+
+    #[inline]
+    ///Generates number within `[0.0, 1.0)`, advancing seed
+    ///
+    ///Uses top 53 bits of generated number, which guarantees uniform distribution
+    pub const fn next_f64(&mut self) -> f64 {
+        const SCALE: f64 = 1.0 / (1u64 << 53) as f64;
+
+        (self.next() >> 11) as f64 * SCALE
+    }
+
+    #[inline]
+    ///Generates `bool` that is `true` with probability `p`, advancing seed
+    ///
+    ///`p` is expected to be within `[0.0, 1.0]`
+    pub const fn next_bool_with(&mut self, p: f64) -> bool {
+        self.next_f64() < p
+    }
+
+    ///Fills `out` with generated numbers, advancing seed
+    ///
+    ///Each number is written as little-endian bytes, with trailing partial word truncated.
+    pub const fn fill_bytes(&mut self, out: &mut [u8]) {
+        let len = out.len();
+        let mut idx = 0;
+
+        while idx < len {
+            let word = self.next().to_le_bytes();
+            let mut word_idx = 0;
+            while word_idx < word.len() && idx < len {
+                out[idx] = word[word_idx];
+                word_idx += 1;
+                idx += 1;
+            }
+        }
+    }
+
     ///Generates new number, decreasing seed
     pub const fn back(&mut self) -> u64 {
         let result = squares(self.key, self.seed);
@@ -71,3 +217,128 @@ impl Squares {
         result
     }
 }
+
+const PCG_MULTIPLIER: u64 = 6364136223846793005;
+const PCG_INCREMENT: u64 = 1442695040888963407;
+
+///PCG32 PRNG (XSH-RR variant)
+///
+///Provides better statistical quality than [Squares](struct.Squares.html), but it cannot be
+///reversed, hence there is no `back()` method.
+///
+///Reference: <https://www.pcg-random.org/>
+pub struct Pcg32 {
+    state: u64,
+    inc: u64,
+}
+
+impl Pcg32 {
+    #[inline(always)]
+    ///Creates new instance using default stream and provided `seed`
+    pub const fn new(seed: u64) -> Self {
+        Self::with_stream(seed, PCG_INCREMENT >> 1)
+    }
+
+    ///Creates new instance using provided `seed` and `stream`
+    ///
+    ///Generators with distinct `stream` produce distinct sequences for the same `seed`
+    pub const fn with_stream(seed: u64, stream: u64) -> Self {
+        let mut result = Self {
+            state: 0,
+            inc: (stream << 1) | 1,
+        };
+        result.step();
+        result.state = result.state.wrapping_add(seed);
+        result.step();
+        result
+    }
+
+    #[inline(always)]
+    const fn step(&mut self) {
+        self.state = self.state.wrapping_mul(PCG_MULTIPLIER).wrapping_add(self.inc);
+    }
+
+    ///Generates new number, advancing state
+    pub const fn next(&mut self) -> u32 {
+        let state = self.state;
+        self.step();
+
+        let xorshifted = (((state >> 18) ^ state) >> 27) as u32;
+        let rot = (state >> 59) as u32;
+        xorshifted.rotate_right(rot)
+    }
+
+    #[inline(always)]
+    ///Generates new 64bit number, combining two consecutive numbers
+    pub const fn next_u64(&mut self) -> u64 {
+        let low = self.next() as u64;
+        let high = self.next() as u64;
+        (high << 32) | low
+    }
+}
+
+#[cfg(feature = "rand_core")]
+impl rand_core::RngCore for Squares {
+    #[inline(always)]
+    fn next_u32(&mut self) -> u32 {
+        self.next() as u32
+    }
+
+    #[inline(always)]
+    fn next_u64(&mut self) -> u64 {
+        self.next()
+    }
+
+    #[inline(always)]
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        Squares::fill_bytes(self, dst)
+    }
+}
+
+#[cfg(feature = "rand_core")]
+impl rand_core::SeedableRng for Squares {
+    type Seed = [u8; 8];
+
+    #[inline(always)]
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::new(u64::from_le_bytes(seed))
+    }
+
+    #[inline(always)]
+    fn seed_from_u64(seed: u64) -> Self {
+        Self::new(seed)
+    }
+}
+
+#[cfg(feature = "rand_core")]
+impl rand_core::RngCore for Pcg32 {
+    #[inline(always)]
+    fn next_u32(&mut self) -> u32 {
+        self.next()
+    }
+
+    #[inline(always)]
+    fn next_u64(&mut self) -> u64 {
+        Pcg32::next_u64(self)
+    }
+
+    #[inline(always)]
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        rand_core::impls::fill_bytes_via_next(self, dst)
+    }
+}
+
+#[cfg(feature = "rand_core")]
+impl rand_core::SeedableRng for Pcg32 {
+    type Seed = [u8; 8];
+
+    #[inline(always)]
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::new(u64::from_le_bytes(seed))
+    }
+
+    #[inline(always)]
+    fn seed_from_u64(seed: u64) -> Self {
+        Self::new(seed)
+    }
+}
@@ -2,7 +2,36 @@
 
 use crate::prng;
 
-use core::{ptr, mem};
+use core::{ptr, mem, ops};
+
+//Separate key, so that whitening keystream is not correlated with swap indexes
+const WHITENING_KEY: u64 = 0xc8e4fd154ce32f6d;
+//Separate key, so that block seeds are not correlated with swap indexes
+const BLOCK_KEY: u64 = 0x3ba9c5e7f1d2846b;
+
+const fn whiten(seed: u64, in_out: &mut [u8]) {
+    let len = in_out.len();
+    let mut idx = 0;
+    let mut prng = prng::Squares::with_key(WHITENING_KEY, seed);
+
+    while idx < len {
+        let word = prng.next().to_le_bytes();
+        let mut word_idx = 0;
+        while word_idx < word.len() && idx < len {
+            in_out[idx] ^= word[word_idx];
+            word_idx += 1;
+            idx += 1;
+        }
+    }
+}
+
+#[inline(always)]
+//PRNG generating swap index of element at `idx`, shared by all shuffles
+const fn prng_at(seed: u64, idx: usize) -> prng::Squares {
+    let mut prng = prng::Squares::new(seed);
+    prng.jump_ahead(idx as u64);
+    prng
+}
 
 const unsafe fn swap<T>(left: *mut T, right: *mut T) {
     let mut tmp = mem::MaybeUninit::<T>::uninit();
@@ -15,6 +44,20 @@ const unsafe fn swap<T>(left: *mut T, right: *mut T) {
     }
 }
 
+const unsafe fn swap_block(ptr: *mut u8, left: usize, right: usize, block_size: usize) {
+    if left == right {
+        return;
+    }
+
+    let mut offset = 0;
+    while offset < block_size {
+        unsafe {
+            swap(ptr.add(left * block_size + offset), ptr.add(right * block_size + offset));
+        }
+        offset += 1;
+    }
+}
+
 ///Implementation of Fisher-Yates shuffling algorithm.
 ///
 ///Implementation uses PRNG with specified key to initialize predictable random generator in order
@@ -22,6 +65,12 @@ const unsafe fn swap<T>(left: *mut T, right: *mut T) {
 ///
 ///Care must be taken to retain this seed.
 ///
+///## Reverse invariant
+///
+///Swap index of position `idx` is derived solely from `seed + idx` (wrapping) and slice length,
+///independently of other positions. Shuffle performs swaps for `idx` in `0..len`, while reverse
+///performs the very same swaps in `(0..len).rev()`, hence undoing them for every seed and length.
+///
 ///Reference: <https://en.wikipedia.org/wiki/Fisher%E2%80%93Yates_shuffle>
 pub struct FisherYates {
     seed: u64,
@@ -36,18 +85,116 @@ impl FisherYates {
         }
     }
 
+    #[inline(always)]
+    ///Returns seed
+    pub const fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    #[cfg(feature = "os_rng")]
     #[inline]
+    ///Creates new instance with random seed generated by OS random number generator
+    ///
+    ///Seed is available via [seed](#method.seed) and must be stored in order to reverse shuffle:
+    ///**losing seed makes shuffled data unrecoverable**.
+    ///
+    ///Returns error if OS is unable to provide entropy
+    pub fn from_entropy() -> Result<Self, getrandom::Error> {
+        getrandom::u64().map(Self::with_seed)
+    }
+
+    #[inline(always)]
+    const fn swap_index(&self, idx: usize, len: usize) -> usize {
+        //Each index uses own PRNG position, so that reverse can reproduce it regardless of how
+        //many numbers were consumed by rejection sampling or in which order indexes are visited
+        let mut prng = self.prng_at(idx);
+        prng.gen_range(len as u64) as usize
+    }
+
+    #[inline(always)]
+    ///Returns PRNG positioned to generate swap index of element at `idx`
+    ///
+    ///Generator is the same for shuffle and reverse, as swap index depends only on position,
+    ///which allows to start at any position without replaying previous ones.
+    pub const fn prng_at(&self, idx: usize) -> prng::Squares {
+        prng_at(self.seed, idx)
+    }
+
+    ///Computes swap indexes of elements `start..start + out.len()` within slice of `len` elements
+    ///
+    ///Computation of swap indexes dominates cost of shuffle, while each index is independent of others.
+    ///Hence chunks of indexes can be computed by separate workers (each owning its own generator
+    ///as returned by [prng_at](#method.prng_at)), after which swaps are applied via
+    ///[shuffle_with_indexes](#method.shuffle_with_indexes) or [reverse_with_indexes](#method.reverse_with_indexes).
+    pub const fn swap_indexes(&self, start: usize, len: usize, out: &mut [usize]) {
+        let mut idx = 0;
+        while idx < out.len() {
+            out[idx] = self.swap_index(start + idx, len);
+            idx += 1;
+        }
+    }
+
+    ///Performs shuffle using swap `indexes` computed by [swap_indexes](#method.swap_indexes)
+    ///
+    ///`indexes` length must be equal to `in_out` length
+    pub const fn shuffle_with_indexes<'a, T>(in_out: &'a mut [T], indexes: &[usize]) -> &'a mut [T] {
+        assert!(in_out.len() == indexes.len(), "indexes length must be equal to in_out length");
+
+        let len = in_out.len();
+        let mut idx = 0;
+        let ptr = in_out.as_mut_ptr();
+
+        while idx < len {
+            //Bounds check, as indexes are user provided
+            assert!(indexes[idx] < len, "swap index is out of bounds");
+            unsafe {
+                swap(ptr.add(idx), ptr.add(indexes[idx]));
+            }
+            idx += 1;
+        }
+
+        in_out
+    }
+
+    ///Performs reverse shuffle using swap `indexes` computed by [swap_indexes](#method.swap_indexes)
+    ///
+    ///`indexes` length must be equal to `in_out` length
+    pub const fn reverse_with_indexes<'a, T>(in_out: &'a mut [T], indexes: &[usize]) -> &'a mut [T] {
+        assert!(in_out.len() == indexes.len(), "indexes length must be equal to in_out length");
+
+        let len = in_out.len();
+        let mut idx = len.wrapping_sub(1);
+        let ptr = in_out.as_mut_ptr();
+
+        while idx < len {
+            //Bounds check, as indexes are user provided
+            assert!(indexes[idx] < len, "swap index is out of bounds");
+            unsafe {
+                swap(ptr.add(idx), ptr.add(indexes[idx]));
+            }
+            idx = idx.wrapping_sub(1);
+        }
+
+        in_out
+    }
+
+    #[inline(always)]
     ///Performs shuffle
     pub const fn shuffle<'a>(&self, in_out: &'a mut [u8]) -> &'a mut [u8] {
+        self.shuffle_slice(in_out)
+    }
+
+    #[inline]
+    ///Performs shuffle of slice with arbitrary elements
+    pub const fn shuffle_slice<'a, T>(&self, in_out: &'a mut [T]) -> &'a mut [T] {
         let len = in_out.len();
         let mut idx = 0;
         let ptr = in_out.as_mut_ptr();
-        let mut prng = prng::Squares::new(self.seed);
 
         while idx < len {
-            let swap_idx = prng.next() % len as u64;
+            let swap_idx = self.swap_index(idx, len);
             unsafe {
-                swap(ptr.add(idx), ptr.add(swap_idx as _));
+                swap(ptr.add(idx), ptr.add(swap_idx));
             }
             idx = idx.saturating_add(1);
         }
@@ -62,18 +209,323 @@ impl FisherYates {
         data
     }
 
-    #[inline]
+    #[inline(always)]
     ///Performs reverse shuffle
     pub const fn reverse<'a>(&self, in_out: &'a mut [u8]) -> &'a mut [u8] {
+        self.reverse_slice(in_out)
+    }
+
+    #[inline]
+    ///Performs reverse shuffle of slice with arbitrary elements
+    pub const fn reverse_slice<'a, T>(&self, in_out: &'a mut [T]) -> &'a mut [T] {
         let len = in_out.len();
         let mut idx = len.wrapping_sub(1);
         let ptr = in_out.as_mut_ptr();
-        let mut prng = prng::Squares::new(self.seed.wrapping_add(idx as u64));
 
         while idx < len {
-            let swap_idx = prng.back() % len as u64;
+            let swap_idx = self.swap_index(idx, len);
+            unsafe {
+                swap(ptr.add(idx), ptr.add(swap_idx));
+            }
+            idx = idx.wrapping_sub(1);
+        }
+
+        in_out
+    }
+
+    #[inline(always)]
+    ///Performs reverse shuffle of constant array
+    pub const fn reverse_const<const N: usize>(&self, mut data: [u8; N]) -> [u8; N] {
+        self.reverse(&mut data);
+        data
+    }
+
+    #[inline]
+    ///Performs obfuscation by XORing every byte with `Squares` keystream followed by shuffle
+    ///
+    ///Unlike plain [shuffle](#method.shuffle), it masks both position and value of bytes, hence
+    ///byte frequencies are not preserved.
+    pub const fn obfuscate<'a>(&self, in_out: &'a mut [u8]) -> &'a mut [u8] {
+        whiten(self.seed, in_out);
+        self.shuffle(in_out)
+    }
+
+    #[inline(always)]
+    ///Performs obfuscation of constant array
+    pub const fn obfuscate_const<const N: usize>(&self, mut data: [u8; N]) -> [u8; N] {
+        self.obfuscate(&mut data);
+        data
+    }
+
+    #[inline]
+    ///Reverses [obfuscate](#method.obfuscate)
+    pub const fn deobfuscate<'a>(&self, in_out: &'a mut [u8]) -> &'a mut [u8] {
+        self.reverse(in_out);
+        whiten(self.seed, in_out);
+        in_out
+    }
+
+    #[inline(always)]
+    ///Reverses obfuscation of constant array
+    pub const fn deobfuscate_const<const N: usize>(&self, mut data: [u8; N]) -> [u8; N] {
+        self.deobfuscate(&mut data);
+        data
+    }
+
+    ///Returns permutation applied by shuffle of `N` elements
+    ///
+    ///Element at position `idx` of shuffled array is element at position `permutation[idx]` of original array.
+    pub const fn permutation<const N: usize>(&self) -> [usize; N] {
+        let mut result = [0usize; N];
+        let mut idx = 0;
+        while idx < N {
+            result[idx] = idx;
+            idx += 1;
+        }
+
+        self.shuffle_slice(&mut result);
+        result
+    }
+
+    ///Returns inverse of [permutation](#method.permutation), i.e. permutation applied by reverse of `N` elements
+    ///
+    ///Element at position `idx` of original array is element at position `inverse_permutation[idx]` of shuffled array.
+    pub const fn inverse_permutation<const N: usize>(&self) -> [usize; N] {
+        let permutation = self.permutation::<N>();
+        let mut result = [0usize; N];
+        let mut idx = 0;
+        while idx < N {
+            result[permutation[idx]] = idx;
+            idx += 1;
+        }
+
+        result
+    }
+
+    ///Verifies that reverse recovers original order after shuffle of `N` elements
+    ///
+    ///Intended to be used at compile time:
+    ///
+    ///```rust
+    ///use obfus::shuffle::FisherYates;
+    ///
+    ///const SHUFFLE: FisherYates = FisherYates::with_seed(1);
+    ///const _: () = assert!(SHUFFLE.verify_reversible::<32>());
+    ///```
+    pub const fn verify_reversible<const N: usize>(&self) -> bool {
+        let mut data = [0usize; N];
+        let mut idx = 0;
+        while idx < N {
+            data[idx] = idx;
+            idx += 1;
+        }
+
+        self.shuffle_slice(&mut data);
+        self.reverse_slice(&mut data);
+
+        let mut idx = 0;
+        while idx < N {
+            if data[idx] != idx {
+                return false;
+            }
+            idx += 1;
+        }
+        true
+    }
+
+    #[inline]
+    ///Performs shuffle only within `range` of `in_out`, leaving the rest untouched
+    pub const fn shuffle_range<'a>(&self, in_out: &'a mut [u8], range: ops::Range<usize>) -> &'a mut [u8] {
+        debug_assert!(range.start <= range.end && range.end <= in_out.len(), "range is out of bounds");
+
+        let (_, tail) = in_out.split_at_mut(range.start);
+        let (target, _) = tail.split_at_mut(range.end - range.start);
+        self.shuffle(target);
+        in_out
+    }
+
+    #[inline]
+    ///Performs reverse shuffle only within `range` of `in_out`, leaving the rest untouched
+    pub const fn reverse_range<'a>(&self, in_out: &'a mut [u8], range: ops::Range<usize>) -> &'a mut [u8] {
+        debug_assert!(range.start <= range.end && range.end <= in_out.len(), "range is out of bounds");
+
+        let (_, tail) = in_out.split_at_mut(range.start);
+        let (target, _) = tail.split_at_mut(range.end - range.start);
+        self.reverse(target);
+        in_out
+    }
+
+    #[inline]
+    ///Performs shuffle of contiguous blocks with size `block_size`, retaining each block's content intact
+    ///
+    ///`in_out` length must be multiple of `block_size`, otherwise trailing bytes are left untouched.
+    pub const fn shuffle_blocks<'a>(&self, in_out: &'a mut [u8], block_size: usize) -> &'a mut [u8] {
+        debug_assert!(block_size > 0, "block_size must be non-zero");
+        debug_assert!(in_out.len() % block_size == 0, "in_out length must be multiple of block_size");
+
+        let len = in_out.len() / block_size;
+        let mut idx = 0;
+        let ptr = in_out.as_mut_ptr();
+
+        while idx < len {
+            let swap_idx = self.swap_index(idx, len);
+            unsafe {
+                swap_block(ptr, idx, swap_idx, block_size);
+            }
+            idx = idx.saturating_add(1);
+        }
+
+        in_out
+    }
+
+    #[inline]
+    ///Performs reverse shuffle of contiguous blocks with size `block_size`
+    ///
+    ///`in_out` length must be multiple of `block_size`, otherwise trailing bytes are left untouched.
+    pub const fn reverse_blocks<'a>(&self, in_out: &'a mut [u8], block_size: usize) -> &'a mut [u8] {
+        debug_assert!(block_size > 0, "block_size must be non-zero");
+        debug_assert!(in_out.len() % block_size == 0, "in_out length must be multiple of block_size");
+
+        let len = in_out.len() / block_size;
+        let mut idx = len.wrapping_sub(1);
+        let ptr = in_out.as_mut_ptr();
+
+        while idx < len {
+            let swap_idx = self.swap_index(idx, len);
+            unsafe {
+                swap_block(ptr, idx, swap_idx, block_size);
+            }
+            idx = idx.wrapping_sub(1);
+        }
+
+        in_out
+    }
+
+    #[inline(always)]
+    ///Returns shuffle applied to content of block `block_idx` by [shuffle_parallel](#method.shuffle_parallel)
+    pub const fn block_shuffle(&self, block_idx: usize) -> Self {
+        Self::with_seed(prng::squares(BLOCK_KEY, self.seed.wrapping_add(block_idx as u64)))
+    }
+
+    #[cfg(feature = "rayon")]
+    ///Performs two level shuffle of `in_out`, processing blocks of `block_size` in parallel
+    ///
+    ///Resulting layout is the same as following sequential steps:
+    ///
+    ///1. content of every block `idx` (including trailing partial block) is shuffled with [block_shuffle(idx)](#method.block_shuffle);
+    ///2. order of full blocks is shuffled via [shuffle_blocks](#method.shuffle_blocks), while trailing partial block remains at the end.
+    ///
+    ///Note that it produces different layout than [shuffle](#method.shuffle), hence it must be reversed via [reverse_parallel](#method.reverse_parallel)
+    pub fn shuffle_parallel<'a>(&self, in_out: &'a mut [u8], block_size: usize) -> &'a mut [u8] {
+        use rayon::prelude::*;
+
+        assert!(block_size > 0, "block_size must be non-zero");
+
+        in_out.par_chunks_mut(block_size).enumerate().for_each(|(idx, block)| {
+            self.block_shuffle(idx).shuffle(block);
+        });
+        let full_len = in_out.len() - in_out.len() % block_size;
+        self.shuffle_blocks(&mut in_out[..full_len], block_size);
+        in_out
+    }
+
+    #[cfg(feature = "rayon")]
+    ///Reverses [shuffle_parallel](#method.shuffle_parallel), processing blocks of `block_size` in parallel
+    ///
+    ///`block_size` must be the same as one used for shuffle
+    pub fn reverse_parallel<'a>(&self, in_out: &'a mut [u8], block_size: usize) -> &'a mut [u8] {
+        use rayon::prelude::*;
+
+        assert!(block_size > 0, "block_size must be non-zero");
+
+        let full_len = in_out.len() - in_out.len() % block_size;
+        self.reverse_blocks(&mut in_out[..full_len], block_size);
+        in_out.par_chunks_mut(block_size).enumerate().for_each(|(idx, block)| {
+            self.block_shuffle(idx).reverse(block);
+        });
+        in_out
+    }
+}
+
+///Implementation of Sattolo's shuffling algorithm.
+///
+///Unlike [FisherYates](struct.FisherYates.html) it always produces single cycle permutation,
+///guaranteeing that no element remains in its original position.
+///
+///Implementation uses PRNG with specified key to initialize predictable random generator in order
+///to guarantee reverse-ability.
+///
+///Care must be taken to retain this seed.
+///
+///Reference: <https://en.wikipedia.org/wiki/Fisher%E2%80%93Yates_shuffle#Sattolo's_algorithm>
+pub struct Sattolo {
+    seed: u64,
+}
+
+impl Sattolo {
+    #[inline]
+    ///Creates new instance with provided `seed`
+    pub const fn with_seed(seed: u64) -> Self {
+        Self {
+            seed,
+        }
+    }
+
+    #[inline(always)]
+    const fn swap_index(&self, idx: usize, len: usize) -> usize {
+        let mut prng = prng_at(self.seed, idx);
+        //Swap only with elements after idx
+        idx + 1 + prng.gen_range((len - idx - 1) as u64) as usize
+    }
+
+    #[inline(always)]
+    ///Performs shuffle
+    pub const fn shuffle<'a>(&self, in_out: &'a mut [u8]) -> &'a mut [u8] {
+        self.shuffle_slice(in_out)
+    }
+
+    #[inline]
+    ///Performs shuffle of slice with arbitrary elements
+    pub const fn shuffle_slice<'a, T>(&self, in_out: &'a mut [T]) -> &'a mut [T] {
+        let len = in_out.len();
+        let mut idx = 0usize;
+        let ptr = in_out.as_mut_ptr();
+
+        while idx.saturating_add(1) < len {
+            let swap_idx = self.swap_index(idx, len);
+            unsafe {
+                swap(ptr.add(idx), ptr.add(swap_idx));
+            }
+            idx = idx.saturating_add(1);
+        }
+
+        in_out
+    }
+
+    #[inline(always)]
+    ///Performs shuffle of constant array
+    pub const fn shuffle_const<const N: usize>(&self, mut data: [u8; N]) -> [u8; N] {
+        self.shuffle(&mut data);
+        data
+    }
+
+    #[inline(always)]
+    ///Performs reverse shuffle
+    pub const fn reverse<'a>(&self, in_out: &'a mut [u8]) -> &'a mut [u8] {
+        self.reverse_slice(in_out)
+    }
+
+    #[inline]
+    ///Performs reverse shuffle of slice with arbitrary elements
+    pub const fn reverse_slice<'a, T>(&self, in_out: &'a mut [T]) -> &'a mut [T] {
+        let len = in_out.len();
+        let mut idx = len.wrapping_sub(2);
+        let ptr = in_out.as_mut_ptr();
+
+        while idx < len {
+            let swap_idx = self.swap_index(idx, len);
             unsafe {
-                swap(ptr.add(idx), ptr.add(swap_idx as _));
+                swap(ptr.add(idx), ptr.add(swap_idx));
             }
             idx = idx.wrapping_sub(1);
         }
@@ -0,0 +1,487 @@
+//!Secret storage implementations
+
+use crate::{Secret, SecretStorage, SecretType, Binary, Text};
+use crate::shuffle::FisherYates;
+use crate::crypto::{Crypto, NONCE_SIZE, TAG_SIZE};
+use crate::utils;
+
+use core::marker;
+
+///Secret storage holding data shuffled with [FisherYates](../shuffle/struct.FisherYates.html)
+///
+///Data is reversed only when secret is retrieved.
+pub struct ShuffledSecret<const N: usize, TAG: SecretType> {
+    data: [u8; N],
+    shuffle: FisherYates,
+    _tag: marker::PhantomData<TAG>
+}
+
+impl<const N: usize> ShuffledSecret<N, Binary> {
+    #[inline]
+    ///Creates new instance from `data` shuffled with `seed`
+    pub const fn new(data: [u8; N], seed: u64) -> Self {
+        Self {
+            data,
+            shuffle: FisherYates::with_seed(seed),
+            _tag: marker::PhantomData
+        }
+    }
+
+    #[inline]
+    ///Retrieves secret value, reversing shuffle
+    pub fn get_secret(&self) -> Secret<N, Binary> {
+        Secret::<N, Binary>::new(self.shuffle.reverse_const(self.data))
+    }
+}
+
+impl<const N: usize> ShuffledSecret<N, Text> {
+    #[inline]
+    ///Creates new instance from `data` shuffled with `seed`, without checking if original content is utf-8
+    pub const unsafe fn new(data: [u8; N], seed: u64) -> Self {
+        Self {
+            data,
+            shuffle: FisherYates::with_seed(seed),
+            _tag: marker::PhantomData
+        }
+    }
+
+    #[inline]
+    ///Creates new instance from `data` shuffled with `seed`, checking if original content is valid utf-8
+    pub fn try_new(data: [u8; N], seed: u64) -> Option<Self> {
+        let shuffle = FisherYates::with_seed(seed);
+        let original = Secret::<N, Binary>::new(shuffle.reverse_const(data));
+        if core::str::from_utf8(original.data()).is_ok() {
+            Some(Self {
+                data,
+                shuffle,
+                _tag: marker::PhantomData
+            })
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    ///Retrieves secret value, reversing shuffle
+    pub fn get_secret(&self) -> Secret<N, Text> {
+        unsafe {
+            Secret::<N, Text>::new(self.shuffle.reverse_const(self.data))
+        }
+    }
+}
+
+impl<const N: usize> SecretStorage<N> for ShuffledSecret<N, Binary> {
+    type Type = Binary;
+
+    #[inline(always)]
+    fn get_secret(&self) -> Secret<N, Self::Type> {
+        ShuffledSecret::<N, Binary>::get_secret(self)
+    }
+}
+
+impl<const N: usize> SecretStorage<N> for ShuffledSecret<N, Text> {
+    type Type = Text;
+
+    #[inline(always)]
+    fn get_secret(&self) -> Secret<N, Self::Type> {
+        ShuffledSecret::<N, Text>::get_secret(self)
+    }
+}
+
+///Text storage holding data shuffled with [FisherYates](../shuffle/struct.FisherYates.html), which is deobfuscated only during access
+///
+///Unlike [ShuffledSecret](struct.ShuffledSecret.html), data is reversed into scratch buffer,
+///which is zeroed as soon as access is finished, hence stored data is never in plaintext.
+///
+///On drop stored data is zeroed
+pub struct LazySecret<const N: usize> {
+    data: [u8; N],
+    shuffle: FisherYates,
+}
+
+//Zeroes plaintext even if access panics
+struct Scratch<const N: usize>([u8; N]);
+
+impl<const N: usize> Drop for Scratch<N> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        utils::secure_zero(&mut self.0);
+    }
+}
+
+impl<const N: usize> LazySecret<N> {
+    #[inline]
+    ///Creates new instance from `data` shuffled with `seed`, without checking if original content is utf-8
+    pub const unsafe fn new(data: [u8; N], seed: u64) -> Self {
+        Self {
+            data,
+            shuffle: FisherYates::with_seed(seed),
+        }
+    }
+
+    #[inline]
+    ///Creates new instance from `data` shuffled with `seed`, checking if original content is valid utf-8
+    pub fn try_new(data: [u8; N], seed: u64) -> Option<Self> {
+        let shuffle = FisherYates::with_seed(seed);
+        let original = Secret::<N, Binary>::new(shuffle.reverse_const(data));
+        if core::str::from_utf8(original.data()).is_ok() {
+            Some(Self {
+                data,
+                shuffle,
+            })
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    ///Creates new instance from `secret`, shuffling it with `seed`
+    pub fn from_secret(secret: &Secret<N, Text>, seed: u64) -> Self {
+        let shuffle = FisherYates::with_seed(seed);
+        Self {
+            data: shuffle.shuffle_const(secret.data),
+            shuffle,
+        }
+    }
+
+    #[inline(always)]
+    ///Access stored data, which is always shuffled outside of [with](#method.with)
+    pub const fn data(&self) -> &[u8; N] {
+        &self.data
+    }
+
+    ///Deobfuscates data into scratch buffer, passing it to `f` and zeroing scratch before returning
+    ///
+    ///Scratch is zeroed even if `f` panics.
+    pub fn with<R>(&mut self, f: impl FnOnce(&str) -> R) -> R {
+        let mut scratch = Scratch(self.data);
+        self.shuffle.reverse(&mut scratch.0);
+        let text = unsafe {
+            core::str::from_utf8_unchecked(&scratch.0)
+        };
+        f(text)
+    }
+}
+
+impl<const N: usize> Drop for LazySecret<N> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        utils::secure_zero(&mut self.data);
+    }
+}
+
+impl<const N: usize> core::fmt::Debug for LazySecret<N> {
+    #[inline]
+    fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(fmt, "LazySecret<{N}>([REDACTED])")
+    }
+}
+
+///Secret storage holding data encrypted with [Crypto](../crypto/struct.Crypto.html)
+///
+///Data is decrypted only when secret is retrieved.
+///
+///Ciphertext and its authentication tag are stored separately, hence `N` is the size of original data.
+pub struct EncryptedSecret<const N: usize, TAG: SecretType> {
+    data: [u8; N],
+    tag: [u8; TAG_SIZE],
+    nonce: [u8; NONCE_SIZE],
+    key: [u8; 32],
+    _tag: marker::PhantomData<TAG>
+}
+
+impl<const N: usize> EncryptedSecret<N, Binary> {
+    #[inline]
+    ///Creates new instance from ciphertext `data` and its authentication `tag`, produced with `nonce` and `key`
+    pub const fn new(data: [u8; N], tag: [u8; TAG_SIZE], nonce: [u8; NONCE_SIZE], key: [u8; 32]) -> Self {
+        Self {
+            data,
+            tag,
+            nonce,
+            key,
+            _tag: marker::PhantomData
+        }
+    }
+}
+
+impl<const N: usize> EncryptedSecret<N, Text> {
+    #[inline]
+    ///Creates new instance from ciphertext `data` and its authentication `tag`, produced with `nonce` and `key`
+    ///
+    ///Original content is assumed to be valid utf-8 without checking
+    pub const unsafe fn new(data: [u8; N], tag: [u8; TAG_SIZE], nonce: [u8; NONCE_SIZE], key: [u8; 32]) -> Self {
+        Self {
+            data,
+            tag,
+            nonce,
+            key,
+            _tag: marker::PhantomData
+        }
+    }
+}
+
+impl<const N: usize, T: SecretType> EncryptedSecret<N, T> {
+    ///Retrieves secret value, decrypting it
+    ///
+    ///Returns error if ciphertext cannot be authenticated
+    pub fn try_get_secret(&self) -> Result<Secret<N, T>, aes_gcm::Error> {
+        //Decrypt directly within secret, so that on failure it is zeroed
+        let mut secret = Secret::<N, T> {
+            data: self.data,
+            _tag: marker::PhantomData
+        };
+        Crypto::new(self.key).decrypt_detached(self.nonce, &mut secret.data, self.tag)?;
+        Ok(secret)
+    }
+
+    #[inline]
+    ///Retrieves secret value, decrypting it
+    ///
+    ///Panics if ciphertext cannot be authenticated, use [try_get_secret](#method.try_get_secret) to handle error
+    pub fn get_secret(&self) -> Secret<N, T> {
+        match self.try_get_secret() {
+            Ok(secret) => secret,
+            Err(_) => panic!("EncryptedSecret: unable to decrypt secret"),
+        }
+    }
+}
+
+impl<const N: usize, T: SecretType> SecretStorage<N> for EncryptedSecret<N, T> {
+    type Type = T;
+
+    #[inline(always)]
+    fn get_secret(&self) -> Secret<N, Self::Type> {
+        EncryptedSecret::<N, T>::get_secret(self)
+    }
+}
+
+#[cfg(feature = "hash")]
+///Size of [HashedSecret](struct.HashedSecret.html) digest
+pub const HASH_SIZE: usize = 32;
+
+#[cfg(feature = "hash")]
+///Verify-only secret storage, holding salted SHA-256 digest instead of secret itself
+///
+///Digest is computed as `SHA-256(salt || secret)`.
+///
+///Note that plain SHA-256 is fast to brute force, hence low entropy secrets (e.g. user passwords)
+///should be stretched first via `crypto::derive_key`
+pub struct HashedSecret<const S: usize> {
+    digest: [u8; HASH_SIZE],
+    salt: [u8; S],
+}
+
+#[cfg(feature = "hash")]
+impl<const S: usize> HashedSecret<S> {
+    #[inline]
+    ///Creates new instance by hashing `secret` with `salt`
+    ///
+    ///`secret` is zeroed once digest is computed.
+    pub fn new<const N: usize, T: SecretType>(secret: Secret<N, T>, salt: [u8; S]) -> Self {
+        Self {
+            digest: Self::hash(secret.data(), &salt),
+            salt,
+        }
+    }
+
+    fn hash(data: &[u8], salt: &[u8; S]) -> [u8; HASH_SIZE] {
+        use sha2::Digest;
+
+        let mut hasher = sha2::Sha256::new();
+        hasher.update(salt);
+        hasher.update(data);
+        hasher.finalize().into()
+    }
+
+    #[inline]
+    ///Verifies whether `candidate` matches original secret
+    ///
+    ///Digests are compared in constant time
+    pub fn verify(&self, candidate: &[u8]) -> bool {
+        let mut digest = Self::hash(candidate, &self.salt);
+        let result = utils::secure_compare(&self.digest, &digest);
+        utils::secure_zero(&mut digest);
+        result
+    }
+
+    #[inline(always)]
+    ///Returns digest
+    pub const fn digest(&self) -> &[u8; HASH_SIZE] {
+        &self.digest
+    }
+
+    #[inline(always)]
+    ///Returns salt
+    pub const fn salt(&self) -> &[u8; S] {
+        &self.salt
+    }
+}
+
+#[cfg(feature = "serde")]
+///Serializable secret storage, holding data shuffled with [FisherYates](../shuffle/struct.FisherYates.html)
+///
+///Only shuffled data and seed are serialized, hence plaintext never appears in serialized form.
+///
+///Note that seed is serialized alongside data, so it is only obfuscation, not encryption.
+pub struct SerializableSecret<const N: usize, TAG: SecretType> {
+    inner: ShuffledSecret<N, TAG>,
+}
+
+#[cfg(feature = "serde")]
+impl<const N: usize, T: SecretType> SerializableSecret<N, T> {
+    #[inline]
+    ///Creates new instance from `secret`, shuffling it with `seed`
+    pub fn from_secret(secret: &Secret<N, T>, seed: u64) -> Self {
+        let shuffle = FisherYates::with_seed(seed);
+        Self {
+            inner: ShuffledSecret {
+                data: shuffle.shuffle_const(secret.data),
+                shuffle,
+                _tag: marker::PhantomData
+            }
+        }
+    }
+
+    #[inline(always)]
+    ///Retrieves secret value, reversing shuffle
+    pub fn get_secret(&self) -> Secret<N, T> {
+        Secret::<N, T> {
+            data: self.inner.shuffle.reverse_const(self.inner.data),
+            _tag: marker::PhantomData
+        }
+    }
+
+    #[inline(always)]
+    ///Returns underlying storage
+    pub fn into_inner(self) -> ShuffledSecret<N, T> {
+        self.inner
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<const N: usize> From<ShuffledSecret<N, Binary>> for SerializableSecret<N, Binary> {
+    #[inline(always)]
+    fn from(inner: ShuffledSecret<N, Binary>) -> Self {
+        Self {
+            inner
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<const N: usize> From<ShuffledSecret<N, Text>> for SerializableSecret<N, Text> {
+    #[inline(always)]
+    fn from(inner: ShuffledSecret<N, Text>) -> Self {
+        Self {
+            inner
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<const N: usize, T: SecretType> SecretStorage<N> for SerializableSecret<N, T> {
+    type Type = T;
+
+    #[inline(always)]
+    fn get_secret(&self) -> Secret<N, Self::Type> {
+        SerializableSecret::<N, T>::get_secret(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+
+    use core::fmt;
+    use serde::de::{self, Visitor, SeqAccess};
+    use serde::ser::SerializeStruct;
+
+    struct BytesRef<'a>(&'a [u8]);
+
+    impl serde::Serialize for BytesRef<'_> {
+        #[inline(always)]
+        fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+            ser.serialize_bytes(self.0)
+        }
+    }
+
+    struct Bytes<const N: usize>([u8; N]);
+
+    struct BytesVisitor<const N: usize>;
+
+    impl<'de, const N: usize> Visitor<'de> for BytesVisitor<N> {
+        type Value = Bytes<N>;
+
+        #[inline(always)]
+        fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(fmt, "{N} bytes")
+        }
+
+        #[inline]
+        fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Self::Value, E> {
+            if value.len() == N {
+                let mut result = [0; N];
+                result.copy_from_slice(value);
+                Ok(Bytes(result))
+            } else {
+                Err(de::Error::invalid_length(value.len(), &self))
+            }
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut result = [0; N];
+            for (idx, byte) in result.iter_mut().enumerate() {
+                match seq.next_element()? {
+                    Some(value) => *byte = value,
+                    None => return Err(de::Error::invalid_length(idx, &self)),
+                }
+            }
+
+            if seq.next_element::<u8>()?.is_some() {
+                return Err(de::Error::invalid_length(N + 1, &self));
+            }
+
+            Ok(Bytes(result))
+        }
+    }
+
+    impl<'de, const N: usize> serde::Deserialize<'de> for Bytes<N> {
+        #[inline(always)]
+        fn deserialize<D: serde::Deserializer<'de>>(des: D) -> Result<Self, D::Error> {
+            des.deserialize_bytes(BytesVisitor::<N>)
+        }
+    }
+
+    #[derive(serde::Deserialize)]
+    #[serde(rename = "SerializableSecret")]
+    struct Repr<const N: usize> {
+        data: Bytes<N>,
+        seed: u64,
+    }
+
+    impl<const N: usize, T: SecretType> serde::Serialize for SerializableSecret<N, T> {
+        fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+            let mut state = ser.serialize_struct("SerializableSecret", 2)?;
+            state.serialize_field("data", &BytesRef(&self.inner.data))?;
+            state.serialize_field("seed", &self.inner.shuffle.seed())?;
+            state.end()
+        }
+    }
+
+    impl<'de, const N: usize> serde::Deserialize<'de> for SerializableSecret<N, Binary> {
+        fn deserialize<D: serde::Deserializer<'de>>(des: D) -> Result<Self, D::Error> {
+            let Repr { data, seed } = Repr::<N>::deserialize(des)?;
+            Ok(ShuffledSecret::<N, Binary>::new(data.0, seed).into())
+        }
+    }
+
+    impl<'de, const N: usize> serde::Deserialize<'de> for SerializableSecret<N, Text> {
+        fn deserialize<D: serde::Deserializer<'de>>(des: D) -> Result<Self, D::Error> {
+            let Repr { data, seed } = Repr::<N>::deserialize(des)?;
+            match ShuffledSecret::<N, Text>::try_new(data.0, seed) {
+                Some(inner) => Ok(inner.into()),
+                None => Err(de::Error::custom("secret is not valid utf-8")),
+            }
+        }
+    }
+}
@@ -0,0 +1,19 @@
+//!Testing hooks to observe secret zeroing
+//!
+//!Counter is global, hence when tests run in parallel it should only be compared with value
+//!observed before the tested code, expecting it to increase by at least the number of dropped secrets.
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+static SECRET_WIPES: AtomicUsize = AtomicUsize::new(0);
+
+#[inline(always)]
+pub(crate) fn record_secret_wipe() {
+    SECRET_WIPES.fetch_add(1, Ordering::SeqCst);
+}
+
+#[inline(always)]
+///Returns number of times [Secret](../struct.Secret.html) was zeroed on drop
+pub fn secret_wipes() -> usize {
+    SECRET_WIPES.load(Ordering::SeqCst)
+}
@@ -1,9 +1,27 @@
 //!Utilities
 
+use crate::prng;
+
 use core::{sync, ptr};
 
+pub mod base64;
+pub mod hex;
+
+#[inline]
 ///Performs volatile memset to ensure compiler cannot optimize operation away
 pub fn secure_memset<T: Copy + Sized + 'static>(data: &mut [T], value: T) {
+    secure_memset_no_fence(data, value);
+    secure_fence();
+}
+
+///Performs volatile memset without trailing compiler fence
+///
+///Intended for hot loops wiping multiple buffers, where single fence after the whole batch is sufficient.
+///
+///**IMPORTANT**: every batch of calls must be followed by [secure_fence](fn.secure_fence.html),
+///otherwise compiler is free to re-order subsequent memory accesses before writes are done.
+///When in doubt, use [secure_memset](fn.secure_memset.html)
+pub fn secure_memset_no_fence<T: Copy + Sized + 'static>(data: &mut [T], value: T) {
     let mut ptr = data.as_mut_ptr();
 
     for _ in 0..data.len() {
@@ -12,6 +30,115 @@ pub fn secure_memset<T: Copy + Sized + 'static>(data: &mut [T], value: T) {
             ptr.add(1)
         }
     }
+}
+
+#[inline(always)]
+///Issues compiler fence, preventing re-ordering of memory accesses across it
+///
+///Must be called after batch of [secure_memset_no_fence](fn.secure_memset_no_fence.html) calls
+pub fn secure_fence() {
+    sync::atomic::compiler_fence(sync::atomic::Ordering::SeqCst);
+}
+
+///Performs volatile memset of bytes in word sized chunks
+///
+///Unlike [secure_memset](fn.secure_memset.html) it writes whole aligned `usize` words per volatile
+///store, with unaligned head and tail written byte by byte. Writes still cannot be elided, but
+///individual bytes within a word are not written separately, which is significantly faster for large buffers.
+///
+///Single compiler fence is issued after all writes.
+pub fn secure_memset_fast(data: &mut [u8], value: u8) {
+    let word = usize::from_ne_bytes([value; core::mem::size_of::<usize>()]);
+    let (head, words, tail) = unsafe {
+        data.align_to_mut::<usize>()
+    };
+
+    secure_memset_no_fence(head, value);
+    secure_memset_no_fence(words, word);
+    secure_memset_no_fence(tail, value);
+    secure_fence();
+}
+
+///Performs volatile fill of `data` by repeating `pattern`, truncating last repetition if necessary
+///
+///Useful to confirm wipe in debugger, by filling memory with recognizable pattern before zeroing it.
+///
+///Does nothing if `pattern` is empty
+pub fn secure_fill_pattern(data: &mut [u8], pattern: &[u8]) {
+    if pattern.is_empty() {
+        return;
+    }
+
+    let mut ptr = data.as_mut_ptr();
+    for idx in 0..data.len() {
+        ptr = unsafe {
+            ptr::write_volatile(ptr, pattern[idx % pattern.len()]);
+            ptr.add(1)
+        }
+    }
+    secure_fence();
+}
+
+#[inline(always)]
+///Performs volatile zeroing to ensure compiler cannot optimize operation away
+///
+///Zero is defined as `T::default()`, refer to [secure_memset](fn.secure_memset.html) for details.
+pub fn secure_zero<T: Copy + Default + Sized + 'static>(data: &mut [T]) {
+    secure_memset(data, T::default())
+}
+
+#[inline]
+///Performs volatile read to ensure compiler cannot optimize operation away
+///
+///Useful to verify memory content after wiping it, but note that it defeats optimizations by design.
+pub fn secure_read<T: Copy + Sized + 'static>(src: &T) -> T {
+    let result = unsafe {
+        ptr::read_volatile(src)
+    };
+
+    sync::atomic::compiler_fence(sync::atomic::Ordering::SeqCst);
+    result
+}
+
+///Compares `left` and `right` in constant time
+///
+///Returns `false` immediately if length differs, otherwise every byte is compared regardless of
+///where mismatch occurs.
+pub fn secure_compare(left: &[u8], right: &[u8]) -> bool {
+    if left.len() != right.len() {
+        return false;
+    }
+
+    let mut acc = 0u8;
+    for idx in 0..left.len() {
+        acc |= left[idx] ^ right[idx];
+    }
 
     sync::atomic::compiler_fence(sync::atomic::Ordering::SeqCst);
+    acc == 0
+}
+
+#[inline(never)]
+//Number derived from seed, that optimizer cannot know in advance
+fn opaque_value(seed: u64) -> u64 {
+    let seed = secure_read(&seed);
+    prng::Squares::new(seed).next()
+}
+
+///Returns `true` for any `seed`, in a way that looks data dependent
+///
+///Relies on `x * (x + 1)` always being even, with `x` produced by [Squares](../prng/struct.Squares.html) from volatile read of `seed`.
+///It is intended to gate decoy code paths, as it resists constant folding, but it is not
+///cryptographically meaningful and can be recognized by skilled analyst.
+pub fn opaque_true(seed: u64) -> bool {
+    let x = opaque_value(seed);
+    x.wrapping_mul(x.wrapping_add(1)) % 2 == 0
+}
+
+///Returns `false` for any `seed`, in a way that looks data dependent
+///
+///Relies on `x * x` never being `2` modulo `4`, refer to [opaque_true](fn.opaque_true.html) for limitations.
+pub fn opaque_false(seed: u64) -> bool {
+    let x = opaque_value(seed);
+    x.wrapping_mul(x) % 4 == 2
 }
@@ -0,0 +1,190 @@
+//!Base64 encoding
+//!
+//!All functions are `const`, allowing to embed encoded data at compile time.
+//!
+//!Reference: <https://www.rfc-editor.org/rfc/rfc4648>
+
+const PAD: u8 = b'=';
+
+///Base64 alphabet
+pub struct Alphabet {
+    chars: &'static [u8; 64],
+}
+
+impl Alphabet {
+    #[inline]
+    const fn decode_char(&self, ch: u8) -> Option<u8> {
+        let mut idx = 0;
+        while idx < self.chars.len() {
+            if self.chars[idx] == ch {
+                return Some(idx as u8);
+            }
+            idx += 1;
+        }
+
+        None
+    }
+}
+
+///Standard alphabet
+pub const STANDARD: Alphabet = Alphabet {
+    chars: b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/",
+};
+
+///URL and filename safe alphabet
+pub const URL_SAFE: Alphabet = Alphabet {
+    chars: b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_",
+};
+
+#[inline(always)]
+///Calculates size of encoded `len` bytes, including padding
+pub const fn encoded_len(len: usize) -> usize {
+    len.div_ceil(3) * 4
+}
+
+///Calculates size of decoded `input`, returning `None` if `input` length is invalid
+pub const fn decoded_len(input: &[u8]) -> Option<usize> {
+    let len = input.len();
+    if len % 4 != 0 {
+        return None;
+    } else if len == 0 {
+        return Some(0);
+    }
+
+    let mut result = len / 4 * 3;
+    if input[len - 1] == PAD {
+        result -= 1;
+        if input[len - 2] == PAD {
+            result -= 1;
+        }
+    }
+
+    Some(result)
+}
+
+///Encodes `input` into `out` using `alphabet`, returning number of written bytes
+///
+///Returns `None` if `out` is smaller than [encoded_len](fn.encoded_len.html)
+pub const fn encode(alphabet: &Alphabet, input: &[u8], out: &mut [u8]) -> Option<usize> {
+    let len = encoded_len(input.len());
+    if out.len() < len {
+        return None;
+    }
+
+    let mut idx = 0;
+    let mut out_idx = 0;
+    while idx < input.len() {
+        let remaining = input.len() - idx;
+        let first = input[idx];
+        let second = if remaining > 1 { input[idx + 1] } else { 0 };
+        let third = if remaining > 2 { input[idx + 2] } else { 0 };
+
+        out[out_idx] = alphabet.chars[(first >> 2) as usize];
+        out[out_idx + 1] = alphabet.chars[(((first & 0x03) << 4) | (second >> 4)) as usize];
+        out[out_idx + 2] = if remaining > 1 {
+            alphabet.chars[(((second & 0x0f) << 2) | (third >> 6)) as usize]
+        } else {
+            PAD
+        };
+        out[out_idx + 3] = if remaining > 2 {
+            alphabet.chars[(third & 0x3f) as usize]
+        } else {
+            PAD
+        };
+
+        idx += 3;
+        out_idx += 4;
+    }
+
+    Some(len)
+}
+
+///Decodes `input` into `out` using `alphabet`, returning number of written bytes
+///
+///Returns `None` if `input` is not valid padded base64 or `out` is smaller than [decoded_len](fn.decoded_len.html)
+pub const fn decode(alphabet: &Alphabet, input: &[u8], out: &mut [u8]) -> Option<usize> {
+    let len = match decoded_len(input) {
+        Some(len) => len,
+        None => return None,
+    };
+    if out.len() < len {
+        return None;
+    }
+
+    let mut idx = 0;
+    let mut out_idx = 0;
+    while idx < input.len() {
+        let is_last = idx + 4 == input.len();
+        let mut quad = [0u8; 4];
+        let mut quad_idx = 0;
+        while quad_idx < quad.len() {
+            let ch = input[idx + quad_idx];
+            //Padding is only allowed as last two characters of final quad
+            if ch == PAD && is_last && quad_idx >= 2 && (quad_idx == 3 || input[idx + 3] == PAD) {
+                quad_idx += 1;
+                continue;
+            }
+            quad[quad_idx] = match alphabet.decode_char(ch) {
+                Some(value) => value,
+                None => return None,
+            };
+            quad_idx += 1;
+        }
+
+        //Unused bits of final quad must be zero, so that encoding is canonical
+        if is_last {
+            let unused = match len - out_idx {
+                1 => quad[1] & 0x0f,
+                2 => quad[2] & 0x03,
+                _ => 0,
+            };
+            if unused != 0 {
+                return None;
+            }
+        }
+
+        let bytes = [
+            (quad[0] << 2) | (quad[1] >> 4),
+            (quad[1] << 4) | (quad[2] >> 2),
+            (quad[2] << 6) | quad[3],
+        ];
+        let mut byte_idx = 0;
+        while byte_idx < bytes.len() && out_idx < len {
+            out[out_idx] = bytes[byte_idx];
+            byte_idx += 1;
+            out_idx += 1;
+        }
+
+        idx += 4;
+    }
+
+    Some(len)
+}
+
+///Encodes `data` using `alphabet`
+///
+///`M` must be equal to [encoded_len](fn.encoded_len.html) of `N`, otherwise it panics
+pub const fn encode_const<const N: usize, const M: usize>(alphabet: &Alphabet, data: [u8; N]) -> [u8; M] {
+    assert!(encoded_len(N) == M, "base64::encode_const: M must be equal to encoded_len(N)");
+
+    let mut result = [0; M];
+    encode(alphabet, &data, &mut result);
+    result
+}
+
+///Decodes `data` using `alphabet`
+///
+///`M` must be equal to [decoded_len](fn.decoded_len.html) of `data`, otherwise it panics, as well
+///as when `data` is not valid base64
+pub const fn decode_const<const N: usize, const M: usize>(alphabet: &Alphabet, data: [u8; N]) -> [u8; M] {
+    match decoded_len(&data) {
+        Some(len) => assert!(len == M, "base64::decode_const: M must be equal to decoded_len(data)"),
+        None => panic!("base64::decode_const: invalid base64 length"),
+    }
+
+    let mut result = [0; M];
+    if decode(alphabet, &data, &mut result).is_none() {
+        panic!("base64::decode_const: invalid base64");
+    }
+    result
+}
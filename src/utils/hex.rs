@@ -0,0 +1,94 @@
+//!Hex encoding
+//!
+//!All functions are `const`, allowing to embed encoded data at compile time.
+
+const CHARS: &[u8; 16] = b"0123456789abcdef";
+
+#[inline]
+const fn decode_char(ch: u8) -> Option<u8> {
+    match ch {
+        b'0'..=b'9' => Some(ch - b'0'),
+        b'a'..=b'f' => Some(ch - b'a' + 10),
+        b'A'..=b'F' => Some(ch - b'A' + 10),
+        _ => None,
+    }
+}
+
+#[inline(always)]
+///Calculates size of encoded `len` bytes
+pub const fn encoded_len(len: usize) -> usize {
+    len * 2
+}
+
+///Encodes `input` into `out` using lowercase characters, returning number of written bytes
+///
+///Returns `None` if `out` is smaller than [encoded_len](fn.encoded_len.html)
+pub const fn encode(input: &[u8], out: &mut [u8]) -> Option<usize> {
+    let len = encoded_len(input.len());
+    if out.len() < len {
+        return None;
+    }
+
+    let mut idx = 0;
+    while idx < input.len() {
+        out[idx * 2] = CHARS[(input[idx] >> 4) as usize];
+        out[idx * 2 + 1] = CHARS[(input[idx] & 0x0f) as usize];
+        idx += 1;
+    }
+
+    Some(len)
+}
+
+///Decodes `input` into `out`, returning number of written bytes
+///
+///Returns `None` if `input` has odd length, contains non-hex characters or `out` is too small
+pub const fn decode(input: &[u8], out: &mut [u8]) -> Option<usize> {
+    if input.len() % 2 != 0 {
+        return None;
+    }
+
+    let len = input.len() / 2;
+    if out.len() < len {
+        return None;
+    }
+
+    let mut idx = 0;
+    while idx < len {
+        let high = match decode_char(input[idx * 2]) {
+            Some(high) => high,
+            None => return None,
+        };
+        let low = match decode_char(input[idx * 2 + 1]) {
+            Some(low) => low,
+            None => return None,
+        };
+        out[idx] = (high << 4) | low;
+        idx += 1;
+    }
+
+    Some(len)
+}
+
+///Encodes `data`
+///
+///`M` must be equal to `N * 2`, otherwise it panics
+pub const fn encode_const<const N: usize, const M: usize>(data: [u8; N]) -> [u8; M] {
+    assert!(encoded_len(N) == M, "hex::encode_const: M must be equal to N * 2");
+
+    let mut result = [0; M];
+    encode(&data, &mut result);
+    result
+}
+
+///Decodes `data`
+///
+///`M` must be equal to `N / 2`, otherwise it panics, as well as when `data` is not valid hex
+pub const fn decode_const<const N: usize, const M: usize>(data: [u8; N]) -> [u8; M] {
+    assert!(N == M * 2, "hex::decode_const: M must be equal to N / 2");
+
+    let mut result = [0; M];
+    if decode(&data, &mut result).is_none() {
+        panic!("hex::decode_const: invalid hex");
+    }
+    result
+}
@@ -0,0 +1,149 @@
+//! XOR obfuscation functions
+
+use crate::prng;
+
+///XORs `data` with [Squares](../prng/struct.Squares.html) keystream of `seed`, starting at byte `offset` of the keystream
+///
+///Result is the same as applying keystream to the whole stream and taking `data.len()` bytes at
+///`offset`, which allows random access to obfuscated data without processing preceding bytes.
+///
+///Applying it twice with the same arguments restores original data.
+pub const fn apply_keystream_at(seed: u64, offset: usize, data: &mut [u8]) {
+    const WORD_SIZE: usize = core::mem::size_of::<u64>();
+
+    let len = data.len();
+    let mut idx = 0;
+    let mut prng = prng::Squares::new(seed);
+    prng.jump_ahead((offset / WORD_SIZE) as u64);
+    //Offset can point in the middle of word
+    let mut word_idx = offset % WORD_SIZE;
+
+    while idx < len {
+        let word = prng.next().to_le_bytes();
+        while word_idx < word.len() && idx < len {
+            data[idx] ^= word[word_idx];
+            word_idx += 1;
+            idx += 1;
+        }
+        word_idx = 0;
+    }
+}
+
+///XOR cipher using [Squares](../prng/struct.Squares.html) as key stream.
+///
+///It is only suitable for casual obfuscation, making data non-greppable within binary, and it
+///provides no real security.
+///
+///Care must be taken to retain this seed.
+pub struct XorCipher {
+    seed: u64,
+}
+
+impl XorCipher {
+    #[inline]
+    ///Creates new instance with provided `seed`
+    pub const fn with_seed(seed: u64) -> Self {
+        Self {
+            seed,
+        }
+    }
+
+    #[inline(always)]
+    ///Performs obfuscation
+    pub const fn obfuscate<'a>(&self, in_out: &'a mut [u8]) -> &'a mut [u8] {
+        apply_keystream_at(self.seed, 0, in_out);
+        in_out
+    }
+
+    #[inline(always)]
+    ///Performs obfuscation of `in_out`, that is located at `offset` within larger obfuscated stream
+    ///
+    ///Refer to [apply_keystream_at](fn.apply_keystream_at.html) for details
+    pub const fn obfuscate_at<'a>(&self, offset: usize, in_out: &'a mut [u8]) -> &'a mut [u8] {
+        apply_keystream_at(self.seed, offset, in_out);
+        in_out
+    }
+
+    #[inline(always)]
+    ///Performs obfuscation of constant array
+    pub const fn obfuscate_const<const N: usize>(&self, mut data: [u8; N]) -> [u8; N] {
+        self.obfuscate(&mut data);
+        data
+    }
+
+    #[inline(always)]
+    ///Reverses obfuscation
+    pub const fn deobfuscate<'a>(&self, in_out: &'a mut [u8]) -> &'a mut [u8] {
+        self.obfuscate(in_out)
+    }
+
+    #[inline(always)]
+    ///Reverses obfuscation of constant array
+    pub const fn deobfuscate_const<const N: usize>(&self, mut data: [u8; N]) -> [u8; N] {
+        self.deobfuscate(&mut data);
+        data
+    }
+}
+
+///Array with every element XOR masked by [Squares](../prng/struct.Squares.html) keystream
+///
+///Element at index `idx` is masked with number generated from `seed + idx`, so elements are
+///demasked individually on access, without exposing the whole table.
+///
+///It is only suitable for casual obfuscation, hiding constants from static analysis of binary's data section.
+pub struct ObfuscatedArray<T, const N: usize> {
+    data: [T; N],
+    seed: u64,
+}
+
+impl<T, const N: usize> ObfuscatedArray<T, N> {
+    #[inline(always)]
+    ///Returns number of elements
+    pub const fn len(&self) -> usize {
+        N
+    }
+
+    #[inline(always)]
+    ///Returns whether array is empty
+    pub const fn is_empty(&self) -> bool {
+        N == 0
+    }
+}
+
+macro_rules! impl_obfuscated_array {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl<const N: usize> ObfuscatedArray<$ty, N> {
+                #[inline(always)]
+                const fn mask(seed: u64, idx: usize) -> $ty {
+                    prng::Squares::new(seed.wrapping_add(idx as u64)).next() as $ty
+                }
+
+                ///Creates new instance by masking every element of `data` using `seed`
+                pub const fn new(mut data: [$ty; N], seed: u64) -> Self {
+                    let mut idx = 0;
+                    while idx < N {
+                        data[idx] ^= Self::mask(seed, idx);
+                        idx += 1;
+                    }
+
+                    Self {
+                        data,
+                        seed,
+                    }
+                }
+
+                #[inline]
+                ///Returns demasked element at `idx`
+                ///
+                ///Panics if `idx` is out of bounds
+                pub fn get(&self, idx: usize) -> $ty {
+                    //Volatile read to prevent compiler from demasking constant table at compile time
+                    crate::utils::secure_read(&self.data[idx]) ^ Self::mask(self.seed, idx)
+                }
+            }
+        )*
+    };
+}
+
+impl_obfuscated_array!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
@@ -1,6 +1,6 @@
 use obfus::crypto;
 use obfus::shuffle::FisherYates;
-use obfus::utils::secure_memset;
+use obfus::utils::{secure_memset, secure_memset_fast, secure_fill_pattern, secure_memset_no_fence, secure_fence, secure_zero, secure_read, secure_compare};
 
 fn inner_should_validate_fisher_yates_shuffle_variety(shuffle: FisherYates) {
     let mut buffer = [0; 1024];
@@ -105,6 +105,43 @@ fn should_verify_secure_memset() {
     assert_eq!(buffer, [1u8; 15]);
 }
 
+#[test]
+fn should_verify_secure_memset_no_fence() {
+    let mut buffers = [[255u8; 15]; 4];
+    for buffer in buffers.iter_mut() {
+        secure_memset_no_fence(buffer, 1);
+    }
+    secure_fence();
+    assert_eq!(buffers, [[1u8; 15]; 4]);
+}
+
+#[test]
+#[ignore]
+fn bench_secure_memset_batch() {
+    const ROUNDS: usize = 100_000;
+    let mut buffers = [[255u8; 32]; 64];
+
+    let now = std::time::Instant::now();
+    for _ in 0..ROUNDS {
+        for buffer in buffers.iter_mut() {
+            secure_memset(buffer, 0);
+        }
+    }
+    let fenced = now.elapsed();
+
+    let now = std::time::Instant::now();
+    for _ in 0..ROUNDS {
+        for buffer in buffers.iter_mut() {
+            secure_memset_no_fence(buffer, 0);
+        }
+        secure_fence();
+    }
+    let batched = now.elapsed();
+
+    assert_eq!(buffers, [[0u8; 32]; 64]);
+    println!("secure_memset: {fenced:?}, secure_memset_no_fence + secure_fence: {batched:?}");
+}
+
 #[test]
 fn should_verify_buffer_api() {
     use aes_gcm::aead::Buffer;
@@ -180,3 +217,2432 @@ fn should_verify_crypto_api() {
     assert_eq!(buffer.len(), DATA.len());
     assert_eq!(buffer.data(), DATA.as_bytes());
 }
+
+#[test]
+fn should_verify_secret_ct_eq() {
+    use obfus::{Secret, Binary};
+
+    let secret = Secret::<4, Binary>::new(*b"test");
+    assert!(secret.ct_eq(b"test"));
+    assert!(!secret.ct_eq(b"tesT"));
+    assert!(!secret.ct_eq(b"Test"));
+    assert!(!secret.ct_eq(b"tes"));
+    assert!(!secret.ct_eq(b"test1"));
+    assert!(!secret.ct_eq(b""));
+
+    assert!(secret.ct_eq_secret(&Secret::<4, Binary>::new(*b"test")));
+    assert!(!secret.ct_eq_secret(&Secret::<4, Binary>::new(*b"tost")));
+    assert!(!secret.ct_eq_secret(&Secret::<3, Binary>::new(*b"tes")));
+    assert!(secret.ct_eq_secret(&Secret::<4, obfus::Text>::try_new(*b"test").unwrap()));
+}
+
+#[test]
+fn should_compare_secrets_with_partial_eq() {
+    use obfus::{Secret, Binary};
+
+    #[derive(PartialEq, Eq, Debug)]
+    struct Credentials {
+        id: u32,
+        key: Secret<4, Binary>,
+    }
+
+    let secret = Secret::<4, Binary>::new(*b"test");
+    assert_eq!(secret, Secret::<4, Binary>::new(*b"test"));
+    //Mismatch at either end must be detected, as every byte is compared
+    assert_ne!(secret, Secret::<4, Binary>::new(*b"Test"));
+    assert_ne!(secret, Secret::<4, Binary>::new(*b"tesT"));
+    assert_ne!(secret, Secret::<4, Binary>::new(*b"TESt"));
+
+    let left = Credentials { id: 1, key: Secret::<4, Binary>::new(*b"test") };
+    assert_eq!(left, Credentials { id: 1, key: Secret::<4, Binary>::new(*b"test") });
+    assert_ne!(left, Credentials { id: 1, key: Secret::<4, Binary>::new(*b"tost") });
+    assert_ne!(left, Credentials { id: 2, key: Secret::<4, Binary>::new(*b"test") });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn should_hash_secret_digest() {
+    use obfus::{Secret, Binary};
+    use std::hash::{BuildHasher, RandomState};
+    use std::collections::HashSet;
+
+    let state = RandomState::new();
+    let secret = Secret::<4, Binary>::new(*b"test");
+    assert_eq!(state.hash_one(&secret), state.hash_one(Secret::<4, Binary>::new(*b"test")));
+    assert_ne!(state.hash_one(&secret), state.hash_one(Secret::<4, Binary>::new(*b"tost")));
+    //Hash is not computed on plaintext
+    assert_ne!(state.hash_one(&secret), state.hash_one(b"test"));
+
+    let mut set = HashSet::new();
+    assert!(set.insert(secret));
+    assert!(!set.insert(Secret::<4, Binary>::new(*b"test")));
+    assert!(set.insert(Secret::<4, Binary>::new(*b"tost")));
+}
+
+#[test]
+fn should_redact_secret_formatting() {
+    use obfus::{Secret, Binary, Text};
+
+    let secret = Secret::<4, Binary>::new(*b"test");
+    assert_eq!(format!("{secret:?}"), "Secret<4>([REDACTED])");
+
+    let secret = Secret::<4, Text>::try_new(*b"text").unwrap();
+    assert_eq!(format!("{secret:?}"), "Secret<4>([REDACTED])");
+    assert_eq!(format!("{:?}", Secret::<4, Binary>::new(*b"1234")), format!("{:?}", Secret::<4, Binary>::new(*b"abcd")));
+}
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn should_verify_zeroize_integration() {
+    use aes_gcm::aead::Buffer;
+    use obfus::zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+    use obfus::{Secret, Binary};
+
+    fn assert_zeroize_on_drop<T: ZeroizeOnDrop>(_: &T) {}
+
+    let mut secret = Secret::<4, Binary>::new(*b"test");
+    assert_zeroize_on_drop(&secret);
+    secret.zeroize();
+    assert_eq!(secret.data(), [0u8; 4]);
+
+    let secret = Zeroizing::new(Secret::<4, Binary>::new(*b"test"));
+    assert_eq!(secret.data(), b"test");
+
+    let mut buffer = crypto::Buffer::<20>::new();
+    buffer.extend_from_slice(b"1234").expect("Success");
+    buffer.zeroize();
+    assert!(buffer.is_empty());
+    assert_eq!(buffer.data(), [0u8; 0]);
+}
+
+#[cfg(feature = "chacha20poly1305")]
+#[test]
+fn should_verify_chacha_crypto_api() {
+    use aes_gcm::aead::Buffer;
+
+    const DATA: &str = "data";
+    const BUFFER_SIZE: usize = crypto::required_buffer_size(DATA.len());
+    const NONCE: [u8; 12] = [2; 12];
+    type DataBuffer = crypto::Buffer<BUFFER_SIZE>;
+
+    let crypto = crypto::ChaChaCrypto::new([1; 32]);
+
+    let mut buffer = DataBuffer::new();
+    buffer.extend_from_slice(DATA.as_bytes()).expect("success");
+
+    crypto.encrypt(NONCE, &mut buffer).expect("to encrypt");
+    assert_eq!(buffer.len(), BUFFER_SIZE);
+    assert_ne!(&buffer.data()[..DATA.len()], DATA.as_bytes());
+
+    crypto.decrypt([0; 12], &mut buffer).expect_err("cannot decrypt with invalid nonce");
+    assert_eq!(buffer.len(), BUFFER_SIZE);
+
+    crypto.decrypt(NONCE, &mut buffer).expect("to decrypt");
+    assert_eq!(buffer.len(), DATA.len());
+    assert_eq!(buffer.data(), DATA.as_bytes());
+}
+
+#[test]
+fn should_verify_crypto_aad_api() {
+    use aes_gcm::aead::Buffer;
+
+    const DATA: &str = "data";
+    const BUFFER_SIZE: usize = crypto::required_buffer_size(DATA.len());
+    const NONCE: [u8; 12] = [2; 12];
+    type DataBuffer = crypto::Buffer<BUFFER_SIZE>;
+
+    let crypto = crypto::Crypto::new([1; 32]);
+
+    let mut buffer = DataBuffer::new();
+    buffer.extend_from_slice(DATA.as_bytes()).expect("success");
+
+    crypto.encrypt_with_aad(NONCE, b"header-1", &mut buffer).expect("to encrypt");
+    assert_eq!(buffer.len(), BUFFER_SIZE);
+
+    crypto.decrypt_with_aad(NONCE, b"header-2", &mut buffer).expect_err("cannot decrypt with invalid aad");
+    assert_eq!(buffer.len(), BUFFER_SIZE);
+    crypto.decrypt(NONCE, &mut buffer).expect_err("cannot decrypt without aad");
+    assert_eq!(buffer.len(), BUFFER_SIZE);
+
+    crypto.decrypt_with_aad(NONCE, b"header-1", &mut buffer).expect("to decrypt");
+    assert_eq!(buffer.len(), DATA.len());
+    assert_eq!(buffer.data(), DATA.as_bytes());
+}
+
+#[cfg(feature = "os_rng")]
+#[cfg_attr(miri, ignore)]
+#[test]
+fn should_generate_random_nonce() {
+    use aes_gcm::aead::Buffer;
+
+    let nonce1 = crypto::random_nonce().expect("to generate nonce");
+    let nonce2 = crypto::random_nonce().expect("to generate nonce");
+    assert_ne!(nonce1, nonce2);
+
+    let crypto = crypto::Crypto::new([1; 32]);
+    let mut buffer = crypto::Buffer::<{crypto::required_buffer_size(4)}>::new();
+    buffer.extend_from_slice(b"data").expect("success");
+    crypto.encrypt(nonce1, &mut buffer).expect("to encrypt");
+    crypto.decrypt(nonce1, &mut buffer).expect("to decrypt");
+    assert_eq!(buffer.data(), b"data");
+}
+
+#[test]
+fn should_verify_nonce_sequence() {
+    let mut sequence = crypto::NonceSequence::with_prefix([1, 2, 3, 4]);
+    assert_eq!(sequence.counter(), 0);
+    let nonce1 = sequence.next().expect("nonce");
+    let nonce2 = sequence.next().expect("nonce");
+    assert_eq!(sequence.counter(), 2);
+    assert_ne!(nonce1, nonce2);
+    assert_eq!(nonce1, [1, 2, 3, 4, 0, 0, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(nonce2, [1, 2, 3, 4, 0, 0, 0, 0, 0, 0, 0, 1]);
+
+    let mut sequence = crypto::NonceSequence::with_counter([0; 4], u64::MAX - 1);
+    assert_eq!(sequence.next().expect("nonce"), [0, 0, 0, 0, 255, 255, 255, 255, 255, 255, 255, 254]);
+    assert_eq!(sequence.next(), None);
+    assert_eq!(sequence.next(), None);
+    assert_eq!(sequence.counter(), u64::MAX);
+}
+
+#[cfg(feature = "kdf")]
+#[test]
+fn should_derive_key_from_password() {
+    const EXPECTED_1: [u8; 32] = [
+        0x12, 0x0f, 0xb6, 0xcf, 0xfc, 0xf8, 0xb3, 0x2c, 0x43, 0xe7, 0x22, 0x52, 0x56, 0xc4, 0xf8, 0x37,
+        0xa8, 0x65, 0x48, 0xc9, 0x2c, 0xcc, 0x35, 0x48, 0x08, 0x05, 0x98, 0x7c, 0xb7, 0x0b, 0xe1, 0x7b,
+    ];
+    const EXPECTED_2: [u8; 32] = [
+        0xae, 0x4d, 0x0c, 0x95, 0xaf, 0x6b, 0x46, 0xd3, 0x2d, 0x0a, 0xdf, 0xf9, 0x28, 0xf0, 0x6d, 0xd0,
+        0x2a, 0x30, 0x3f, 0x8e, 0xf3, 0xc2, 0x51, 0xdf, 0xd6, 0xe2, 0xd8, 0x5a, 0x95, 0x47, 0x4c, 0x43,
+    ];
+
+    assert_eq!(crypto::derive_key(b"password", b"salt", 1), EXPECTED_1);
+    assert_eq!(crypto::derive_key(b"password", b"salt", 2), EXPECTED_2);
+
+    let key = crypto::derive_key(b"password", b"salt", 2);
+    let _ = crypto::Crypto::new(key);
+}
+
+#[test]
+fn should_verify_buffer_capacity_and_clear() {
+    use aes_gcm::aead::Buffer;
+
+    let mut buffer = crypto::Buffer::<{crypto::required_buffer_size(6)}>::new();
+    assert_eq!(buffer.capacity(), 6);
+
+    buffer.extend_from_slice(b"123456").expect("Success");
+    assert_eq!(buffer.capacity(), 6);
+    assert_eq!(buffer.len(), 6);
+
+    buffer.clear();
+    assert!(buffer.is_empty());
+    assert_eq!(buffer.data(), [0u8; 0]);
+    assert_eq!(buffer.capacity(), 6);
+
+    buffer.extend_from_slice(b"12").expect("Success");
+    assert_eq!(buffer.data(), b"12");
+}
+
+#[test]
+fn should_verify_buffer_from_slice() {
+    use aes_gcm::aead::Buffer;
+
+    const NONCE: [u8; 12] = [2; 12];
+    type DataBuffer = crypto::Buffer<{crypto::required_buffer_size(4)}>;
+
+    assert!(DataBuffer::from_slice(b"12345").is_err());
+    let empty = DataBuffer::from_slice(b"").expect("Success");
+    assert!(empty.is_empty());
+
+    let crypto = crypto::Crypto::new([1; 32]);
+    let mut buffer = DataBuffer::from_slice(b"data").expect("Success");
+    assert_eq!(buffer.data(), b"data");
+
+    crypto.encrypt(NONCE, &mut buffer).expect("to encrypt");
+    crypto.decrypt(NONCE, &mut buffer).expect("to decrypt");
+    assert_eq!(buffer.data(), b"data");
+}
+
+#[test]
+fn should_validate_fisher_yates_shuffle_slice() {
+    const SHUFFLE: FisherYates = FisherYates::with_seed(1);
+
+    let mut expected = [0u32; 16];
+    for (idx, value) in expected.iter_mut().enumerate() {
+        *value = (idx as u32) * 1000;
+    }
+
+    let mut buffer = expected;
+    SHUFFLE.shuffle_slice(&mut buffer);
+    assert_ne!(buffer, expected);
+    SHUFFLE.reverse_slice(&mut buffer);
+    assert_eq!(buffer, expected);
+
+    let mut empty: [u32; 0] = [];
+    SHUFFLE.shuffle_slice(&mut empty);
+    SHUFFLE.reverse_slice(&mut empty);
+}
+
+#[cfg(feature = "rand_core")]
+#[test]
+fn should_verify_squares_rand_core() {
+    use obfus::prng::Squares;
+    use obfus::rand_core::{RngCore, SeedableRng};
+
+    let mut rng = Squares::seed_from_u64(1);
+    let mut expected = Squares::new(1);
+    assert_eq!(rng.next_u64(), expected.next());
+    assert_eq!(rng.next_u32(), expected.next() as u32);
+
+    let mut buffer1 = [0u8; 13];
+    let mut buffer2 = [0u8; 13];
+    Squares::from_seed(1u64.to_le_bytes()).fill_bytes(&mut buffer1);
+    Squares::seed_from_u64(1).fill_bytes(&mut buffer2);
+    assert_eq!(buffer1, buffer2);
+
+    let mut expected = Squares::new(1);
+    assert_eq!(buffer1[..8], expected.next().to_le_bytes());
+    assert_eq!(buffer1[8..], expected.next().to_le_bytes()[..5]);
+}
+
+#[test]
+fn should_verify_squares_fill_bytes() {
+    use obfus::prng::Squares;
+
+    const FILLED: [u8; 9] = {
+        let mut buffer = [0u8; 9];
+        Squares::new(5).fill_bytes(&mut buffer);
+        buffer
+    };
+
+    let mut buffer = [0u8; 9];
+    Squares::new(5).fill_bytes(&mut buffer);
+    assert_eq!(buffer, FILLED);
+
+    let mut expected = Squares::new(5);
+    assert_eq!(buffer[..8], expected.next().to_le_bytes());
+    assert_eq!(buffer[8], expected.next().to_le_bytes()[0]);
+
+    let mut prng = Squares::new(5);
+    prng.fill_bytes(&mut []);
+    assert_eq!(prng.next(), Squares::new(5).next());
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn should_verify_squares_gen_range_distribution() {
+    use obfus::prng::Squares;
+
+    const BOUND: u64 = 6;
+    const SAMPLES: usize = 60_000;
+    const EXPECTED: usize = SAMPLES / BOUND as usize;
+
+    let mut prng = Squares::new(1);
+    let mut buckets = [0usize; BOUND as usize];
+    for _ in 0..SAMPLES {
+        let value = prng.gen_range(BOUND);
+        assert!(value < BOUND);
+        buckets[value as usize] += 1;
+    }
+
+    for bucket in buckets {
+        assert!(bucket.abs_diff(EXPECTED) < EXPECTED / 10, "bucket={bucket} is too far from {EXPECTED}");
+    }
+
+    let mut prng = Squares::new(1);
+    assert_eq!(prng.gen_range(1), 0);
+    assert_eq!(prng.gen_range_biased(1), 0);
+    assert_eq!(Squares::new(1).gen_range_biased(BOUND), Squares::new(1).next() % BOUND);
+}
+
+#[test]
+fn should_verify_squares_jump() {
+    use obfus::prng::Squares;
+
+    let mut expected = Squares::new(0);
+    for _ in 0..5 {
+        expected.next();
+    }
+    let expected = expected.next();
+
+    let mut prng = Squares::new(0);
+    prng.jump_to(5);
+    assert_eq!(prng.next(), expected);
+
+    let mut prng = Squares::new(2);
+    prng.jump_ahead(3);
+    assert_eq!(prng.next(), expected);
+
+    let mut prng = Squares::new(u64::MAX);
+    prng.jump_ahead(6);
+    assert_eq!(prng.next(), expected);
+}
+
+#[test]
+fn should_verify_secret_len() {
+    use obfus::{Secret, Binary};
+
+    const fn secret_len<const N: usize>(secret: &Secret<N, Binary>) -> usize {
+        secret.len()
+    }
+
+    let secret = Secret::<4, Binary>::new(*b"test");
+    assert_eq!(secret_len(&secret), 4);
+    assert_eq!(secret.len(), 4);
+    assert!(!secret.is_empty());
+    assert!(Secret::<0, Binary>::new([]).is_empty());
+    assert_eq!(Secret::<0, Binary>::new([]).len(), 0);
+}
+
+#[test]
+fn should_verify_secret_map() {
+    use core::mem::ManuallyDrop;
+    use obfus::{Secret, Binary};
+
+    let mut data = [0u8; 32];
+    for (idx, byte) in data.iter_mut().enumerate() {
+        *byte = idx as u8 + 1;
+    }
+    let secret = Secret::<32, Binary>::new(data);
+
+    let mapped = secret.map(|data| {
+        let mut result = [0u8; 16];
+        result.copy_from_slice(&data[..16]);
+        result
+    });
+    assert_eq!(mapped.data(), &data[..16]);
+
+    let mut mapped = ManuallyDrop::new(mapped);
+    unsafe {
+        core::ptr::drop_in_place(&mut *mapped);
+    }
+    assert_eq!(mapped.data(), [0u8; 16]);
+}
+
+#[cfg(feature = "macros")]
+#[test]
+fn should_verify_obfuscate_macro() {
+    use obfus::{Secret, Binary, Text};
+
+    const SHUFFLE: FisherYates = FisherYates::with_seed(0x1234);
+
+    let text: Secret<10, Text> = obfus::obfuscate!("my api key", seed = 0x1234);
+    assert_eq!(text.as_str(), "my api key");
+
+    let binary: Secret<4, Binary> = obfus::obfuscate!(b"\x00\x01te", seed = 1,);
+    assert_eq!(binary.data(), b"\x00\x01te");
+
+    let empty: Secret<0, Text> = obfus::obfuscate!("", seed = 0);
+    assert_eq!(empty.as_str(), "");
+
+    let unicode = obfus::obfuscate!("секрет", seed = 0x1234);
+    assert_eq!(unicode.as_str(), "секрет");
+    assert_ne!(SHUFFLE.shuffle_const(*b"my api key"), *b"my api key");
+}
+
+#[cfg(feature = "macros")]
+#[test]
+fn should_verify_secret_from_env_macro() {
+    let name = obfus::secret_from_env!("CARGO_PKG_NAME", seed = 0x55);
+    assert_eq!(name.as_str(), env!("CARGO_PKG_NAME"));
+
+    let version = obfus::secret_from_env!("CARGO_PKG_VERSION", seed = 1,);
+    assert_eq!(version.as_str(), env!("CARGO_PKG_VERSION"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn should_fail_to_compile_ui_cases() {
+    let cases = trybuild::TestCases::new();
+    #[cfg(feature = "macros")]
+    cases.compile_fail("tests/ui/secret_from_env_missing.rs");
+    cases.compile_fail("tests/ui/secret_display.rs");
+    cases.compile_fail("tests/ui/assert_buffer_fits.rs");
+    cases.compile_fail("tests/ui/shuffled_const_runtime_seed.rs");
+}
+
+#[test]
+fn should_verify_reveal_macro() {
+    use obfus::{Secret, Binary};
+
+    let text = obfus::secret_text!("revealed");
+    let revealed: &str = obfus::reveal!(text);
+    assert_eq!(revealed, "revealed");
+    assert_eq!(format!("{}", obfus::reveal!(text)), "revealed");
+
+    let binary = Secret::<4, Binary>::new(*b"test");
+    let revealed: &[u8] = obfus::reveal!(binary);
+    assert_eq!(revealed, b"test");
+}
+
+#[test]
+fn should_verify_assert_buffer_fits() {
+    const DATA_LEN: usize = 8;
+    const BUFFER_SIZE: usize = crypto::required_buffer_size(DATA_LEN);
+
+    crypto::assert_buffer_fits!(DATA_LEN, BUFFER_SIZE);
+    obfus::assert_buffer_fits!(DATA_LEN, BUFFER_SIZE + 1);
+
+    let buffer = crypto::Buffer::<BUFFER_SIZE>::from_slice(&[1; DATA_LEN]).expect("to fit");
+    assert_eq!(buffer.data(), [1; DATA_LEN]);
+}
+
+#[test]
+fn should_verify_xor_cipher() {
+    use obfus::xor::XorCipher;
+
+    const CIPHER: XorCipher = XorCipher::with_seed(1);
+    const OBFUSCATED: [u8; 4] = CIPHER.obfuscate_const(*b"test");
+    const DEOBFUSCATED: [u8; 4] = CIPHER.deobfuscate_const(OBFUSCATED);
+
+    assert_ne!(OBFUSCATED, *b"test");
+    assert_eq!(DEOBFUSCATED, *b"test");
+
+    let mut empty: [u8; 0] = [];
+    CIPHER.obfuscate(&mut empty);
+    CIPHER.deobfuscate(&mut empty);
+
+    let mut single = [b'a'];
+    CIPHER.obfuscate(&mut single);
+    assert_ne!(single, [b'a']);
+    CIPHER.deobfuscate(&mut single);
+    assert_eq!(single, [b'a']);
+
+    let mut expected = [0u8; 1024];
+    for (idx, byte) in expected.iter_mut().enumerate() {
+        *byte = idx as u8;
+    }
+    let mut buffer = expected;
+    CIPHER.obfuscate(&mut buffer);
+    assert_ne!(buffer, expected);
+    CIPHER.deobfuscate(&mut buffer);
+    assert_eq!(buffer, expected);
+}
+
+#[test]
+fn should_verify_shuffled_secret_storage() {
+    use obfus::{SecretStorage, Binary, Text};
+    use obfus::storage::ShuffledSecret;
+
+    const SEED: u64 = 0x1234;
+    const SHUFFLED: [u8; 11] = FisherYates::with_seed(SEED).shuffle_const(*b"hello world");
+    const BINARY: ShuffledSecret<11, Binary> = ShuffledSecret::<11, Binary>::new(SHUFFLED, SEED);
+    const TEXT: ShuffledSecret<11, Text> = unsafe { ShuffledSecret::<11, Text>::new(SHUFFLED, SEED) };
+
+    fn get_secret<const N: usize, T: SecretStorage<N>>(storage: &T) -> obfus::Secret<N, T::Type> {
+        storage.get_secret()
+    }
+
+    assert_ne!(&SHUFFLED, b"hello world");
+    assert_eq!(BINARY.get_secret().data(), b"hello world");
+    assert_eq!(get_secret(&BINARY).data(), b"hello world");
+    assert_eq!(TEXT.get_secret().as_str(), "hello world");
+    assert_eq!(get_secret(&TEXT).as_str(), "hello world");
+
+    let text = ShuffledSecret::<11, Text>::try_new(SHUFFLED, SEED).expect("valid utf-8");
+    assert_eq!(text.get_secret().as_str(), "hello world");
+    let invalid = FisherYates::with_seed(SEED).shuffle_const([255u8; 11]);
+    assert!(ShuffledSecret::<11, Text>::try_new(invalid, SEED).is_none());
+}
+
+#[test]
+fn should_verify_lazy_secret_storage() {
+    use obfus::storage::LazySecret;
+
+    const SEED: u64 = 0x1234;
+    const SHUFFLED: [u8; 11] = FisherYates::with_seed(SEED).shuffle_const(*b"hello world");
+
+    let mut secret = LazySecret::<11>::try_new(SHUFFLED, SEED).expect("valid utf-8");
+    assert_eq!(format!("{secret:?}"), "LazySecret<11>([REDACTED])");
+    let len = secret.with(|text| {
+        assert_eq!(text, "hello world");
+        text.len()
+    });
+    assert_eq!(len, 11);
+    assert_eq!(secret.data(), &SHUFFLED);
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| secret.with(|_| panic!("access failure"))));
+    assert!(result.is_err());
+    assert_eq!(secret.data(), &SHUFFLED);
+
+    let mut secret = LazySecret::from_secret(&obfus::Secret::<11, obfus::Text>::try_new(*b"hello world").unwrap(), SEED);
+    assert_eq!(secret.data(), &SHUFFLED);
+    secret.with(|text| assert_eq!(text, "hello world"));
+    assert_eq!(secret.data(), &SHUFFLED);
+
+    let invalid = FisherYates::with_seed(SEED).shuffle_const([255u8; 11]);
+    assert!(LazySecret::<11>::try_new(invalid, SEED).is_none());
+}
+
+#[test]
+fn should_verify_encrypted_secret_storage() {
+    use aes_gcm::aead::Buffer;
+    use obfus::{SecretStorage, Binary, Text};
+    use obfus::storage::EncryptedSecret;
+
+    const DATA: &str = "hello world";
+    const KEY: [u8; 32] = [1; 32];
+    const NONCE: [u8; 12] = [2; 12];
+
+    let crypto = crypto::Crypto::new(KEY);
+    let mut buffer = crypto::Buffer::<{crypto::required_buffer_size(DATA.len())}>::from_slice(DATA.as_bytes()).expect("Success");
+    crypto.encrypt(NONCE, &mut buffer).expect("to encrypt");
+
+    let mut ciphertext = [0u8; 11];
+    let mut tag = [0u8; 16];
+    ciphertext.copy_from_slice(&buffer.data()[..DATA.len()]);
+    tag.copy_from_slice(&buffer.data()[DATA.len()..]);
+
+    let text = unsafe { EncryptedSecret::<11, Text>::new(ciphertext, tag, NONCE, KEY) };
+    assert_eq!(text.try_get_secret().expect("to decrypt").as_str(), DATA);
+    assert_eq!(SecretStorage::get_secret(&text).as_str(), DATA);
+
+    let binary = EncryptedSecret::<11, Binary>::new(ciphertext, tag, NONCE, KEY);
+    assert_eq!(binary.get_secret().data(), DATA.as_bytes());
+
+    let invalid = EncryptedSecret::<11, Binary>::new(ciphertext, tag, [0; 12], KEY);
+    assert!(invalid.try_get_secret().is_err());
+    assert_eq!(buffer.len(), crypto::required_buffer_size(DATA.len()));
+}
+
+#[test]
+#[should_panic]
+fn should_panic_on_encrypted_secret_storage_failure() {
+    use obfus::Binary;
+    use obfus::storage::EncryptedSecret;
+
+    let invalid = EncryptedSecret::<4, Binary>::new([0; 4], [0; 16], [0; 12], [0; 32]);
+    invalid.get_secret();
+}
+
+#[test]
+fn should_verify_crypto_detached_api() {
+    const DATA: &str = "hello world";
+    const NONCE: [u8; 12] = [2; 12];
+
+    let crypto = crypto::Crypto::new([1; 32]);
+
+    let mut buffer = [0u8; 11];
+    buffer.copy_from_slice(DATA.as_bytes());
+    let tag = crypto.encrypt_detached(NONCE, &mut buffer).expect("to encrypt");
+    assert_ne!(buffer, DATA.as_bytes());
+
+    crypto.decrypt_detached(NONCE, &mut buffer, [0; crypto::TAG_SIZE]).expect_err("cannot decrypt with invalid tag");
+    crypto.decrypt_detached([0; 12], &mut buffer, tag).expect_err("cannot decrypt with invalid nonce");
+    crypto.decrypt_detached(NONCE, &mut buffer, tag).expect("to decrypt");
+    assert_eq!(buffer, DATA.as_bytes());
+
+    let mut empty = [0u8; 0];
+    let tag = crypto.encrypt_detached(NONCE, &mut empty).expect("to encrypt");
+    crypto.decrypt_detached(NONCE, &mut empty, tag).expect("to decrypt");
+}
+
+#[cfg(feature = "chacha20poly1305")]
+#[test]
+fn should_verify_chacha_crypto_detached_api() {
+    const DATA: &str = "hello world";
+    const NONCE: [u8; 12] = [2; 12];
+
+    let crypto = crypto::ChaChaCrypto::new([1; 32]);
+
+    let mut buffer = [0u8; 11];
+    buffer.copy_from_slice(DATA.as_bytes());
+    let tag = crypto.encrypt_detached(NONCE, &mut buffer).expect("to encrypt");
+    assert_ne!(buffer, DATA.as_bytes());
+
+    crypto.decrypt_detached(NONCE, &mut buffer, [0; crypto::TAG_SIZE]).expect_err("cannot decrypt with invalid tag");
+    crypto.decrypt_detached(NONCE, &mut buffer, tag).expect("to decrypt");
+    assert_eq!(buffer, DATA.as_bytes());
+}
+
+#[test]
+fn should_verify_crypto_with_truncated_tag() {
+    use crypto::Cipher;
+
+    const DATA: &str = "hello world";
+    const NONCE: [u8; 12] = [2; 12];
+    const SIZE: usize = crypto::required_buffer_size_for::<crypto::CryptoTag12, 12>(DATA.len());
+
+    assert_eq!(<crypto::CryptoTag12 as Cipher<12>>::TAG_SIZE, 12);
+    assert_eq!(<crypto::Crypto as Cipher>::TAG_SIZE, crypto::TAG_SIZE);
+    assert_eq!(SIZE, crypto::required_buffer_size(DATA.len()) - 4);
+    assert_eq!(crypto::required_buffer_size_for::<crypto::Crypto, 16>(DATA.len()), crypto::required_buffer_size(DATA.len()));
+
+    let crypto = crypto::CryptoTag12::new([1; 32]);
+    let mut buffer = crypto::Buffer::<SIZE, 12>::from_slice(DATA.as_bytes()).expect("to fit");
+    assert_eq!(buffer.capacity(), DATA.len());
+    buffer.push(0).expect_err("tag space is reserved");
+
+    crypto.encrypt(NONCE, &mut buffer).expect("to encrypt");
+    assert_eq!(buffer.data().len(), SIZE);
+    assert_eq!(buffer.plaintext_len(), Some(DATA.len()));
+    assert_ne!(&buffer[..DATA.len()], DATA.as_bytes());
+
+    let mut tampered = crypto::Buffer::<SIZE, 12>::new();
+    crypto.encrypt_to(NONCE, DATA.as_bytes(), &mut tampered).expect("to encrypt");
+    assert_eq!(tampered, buffer);
+    tampered.data_mut()[0] ^= 1;
+    crypto.decrypt(NONCE, &mut tampered).expect_err("cannot decrypt tampered data");
+
+    crypto.decrypt(NONCE, &mut buffer).expect("to decrypt");
+    assert_eq!(buffer, DATA.as_bytes());
+
+    let mut detached = [0u8; 11];
+    detached.copy_from_slice(DATA.as_bytes());
+    let tag: [u8; 12] = crypto.encrypt_detached(NONCE, &mut detached).expect("to encrypt");
+    crypto.decrypt_detached(NONCE, &mut detached, tag).expect("to decrypt");
+    assert_eq!(detached, DATA.as_bytes());
+}
+
+#[test]
+fn should_verify_truncated_tag_with_short_payloads() {
+    use crypto::Cipher;
+
+    fn roundtrip<C: Cipher<TAG, Nonce = [u8; 12]>, const N: usize, const TAG: usize>(cipher: &C, data: &[u8]) {
+        let mut buffer = crypto::Buffer::<N, TAG>::from_slice(data).expect("to fit");
+        assert_eq!(buffer.capacity(), N - TAG);
+        cipher.encrypt([2; 12], &mut buffer).expect("to encrypt");
+        assert_eq!(buffer.data().len(), data.len() + TAG);
+        cipher.decrypt([2; 12], &mut buffer).expect("to decrypt");
+        assert_eq!(buffer, data);
+    }
+
+    let crypto = crypto::CryptoTag12::new([1; 32]);
+    //Buffer must have capacity for data, but it can be left empty
+    roundtrip::<_, { crypto::required_buffer_size_for::<crypto::CryptoTag12, 12>(1) }, 12>(&crypto, b"");
+    roundtrip::<_, { crypto::required_buffer_size_for::<crypto::CryptoTag12, 12>(1) }, 12>(&crypto, b"a");
+    roundtrip::<_, { crypto::required_buffer_size_for::<crypto::CryptoTag12, 12>(2) }, 12>(&crypto, b"ab");
+    roundtrip::<_, { crypto::required_buffer_size_for::<crypto::CryptoTag12, 12>(3) }, 12>(&crypto, b"abc");
+    roundtrip::<_, { crypto::required_buffer_size_for::<crypto::CryptoTag12, 12>(4) }, 12>(&crypto, b"abcd");
+
+    #[cfg(feature = "hazmat")]
+    {
+        let crypto = crypto::CryptoTag8::new([1; 32]);
+        roundtrip::<_, { crypto::required_buffer_size_for::<crypto::CryptoTag8, 8>(1) }, 8>(&crypto, b"");
+        roundtrip::<_, { crypto::required_buffer_size_for::<crypto::CryptoTag8, 8>(1) }, 8>(&crypto, b"a");
+        roundtrip::<_, { crypto::required_buffer_size_for::<crypto::CryptoTag8, 8>(2) }, 8>(&crypto, b"ab");
+        roundtrip::<_, { crypto::required_buffer_size_for::<crypto::CryptoTag8, 8>(3) }, 8>(&crypto, b"abc");
+    }
+}
+
+#[test]
+fn should_verify_crypto_stream_api() {
+    use aes_gcm::aead::Buffer;
+
+    const KEY: [u8; 32] = [1; 32];
+    const PREFIX: [u8; crypto::STREAM_NONCE_SIZE] = [3; crypto::STREAM_NONCE_SIZE];
+    const CHUNKS: [&[u8]; 3] = [b"chunk1", b"chunk2", b"last"];
+    type ChunkBuffer = crypto::Buffer<{crypto::required_buffer_size(6)}>;
+
+    fn copy(buffer: &ChunkBuffer) -> ChunkBuffer {
+        let mut result = ChunkBuffer::new();
+        result.extend_from_slice(buffer.data()).expect("Success");
+        result
+    }
+
+    let mut encryptor = crypto::StreamEncryptor::new(crypto::Crypto::new(KEY), PREFIX);
+    let mut encrypted = [ChunkBuffer::new(), ChunkBuffer::new(), ChunkBuffer::new()];
+    for (buffer, chunk) in encrypted.iter_mut().zip(CHUNKS) {
+        buffer.extend_from_slice(chunk).expect("Success");
+    }
+    encryptor.encrypt_next(&mut encrypted[0]).expect("to encrypt");
+    encryptor.encrypt_next(&mut encrypted[1]).expect("to encrypt");
+    encryptor.encrypt_last(&mut encrypted[2]).expect("to encrypt");
+
+    //Truncated stream
+    let mut decryptor = crypto::StreamDecryptor::new(crypto::Crypto::new(KEY), PREFIX);
+    let mut chunk = copy(&encrypted[0]);
+    decryptor.decrypt_next(&mut chunk).expect("to decrypt");
+    let mut chunk = copy(&encrypted[1]);
+    decryptor.decrypt_last(&mut chunk).expect_err("cannot decrypt truncated stream");
+
+    //Re-ordered stream
+    let mut decryptor = crypto::StreamDecryptor::new(crypto::Crypto::new(KEY), PREFIX);
+    let mut chunk = copy(&encrypted[1]);
+    decryptor.decrypt_next(&mut chunk).expect_err("cannot decrypt out of order");
+
+    //Full stream
+    let mut decryptor = crypto::StreamDecryptor::new(crypto::Crypto::new(KEY), PREFIX);
+    let [mut first, mut second, mut last] = encrypted;
+    decryptor.decrypt_next(&mut first).expect("to decrypt");
+    decryptor.decrypt_next(&mut second).expect("to decrypt");
+    let mut invalid_last = copy(&last);
+    decryptor.decrypt_next(&mut invalid_last).expect_err("cannot decrypt last chunk as next");
+    decryptor.decrypt_last(&mut last).expect("to decrypt");
+    assert_eq!(first.data(), CHUNKS[0]);
+    assert_eq!(second.data(), CHUNKS[1]);
+    assert_eq!(last.data(), CHUNKS[2]);
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn should_validate_sattolo_shuffle() {
+    use obfus::shuffle::Sattolo;
+
+    const SHUFFLE: Sattolo = Sattolo::with_seed(1);
+    const REVERSED: [u8; 4] = SHUFFLE.shuffle_const(*b"test");
+    const UNREVERSED: [u8; 4] = SHUFFLE.reverse_const(REVERSED);
+    assert_eq!("test", core::str::from_utf8(&UNREVERSED).unwrap());
+
+    SHUFFLE.shuffle_const([]);
+    SHUFFLE.reverse_const([]);
+    assert_eq!(SHUFFLE.shuffle_const([1]), [1]);
+    assert_eq!(SHUFFLE.reverse_const([1]), [1]);
+
+    let mut expected = [0u32; 64];
+    for (idx, value) in expected.iter_mut().enumerate() {
+        *value = idx as u32;
+    }
+
+    for seed in [0, 1, 0x1234, u64::MAX - 1, u64::MAX] {
+        let shuffle = Sattolo::with_seed(seed);
+        for len in 2..expected.len() {
+            let mut buffer = expected;
+            shuffle.shuffle_slice(&mut buffer[..len]);
+            for idx in 0..len {
+                assert_ne!(buffer[idx], expected[idx], "seed={seed} len={len} idx={idx} has fixed point");
+            }
+            shuffle.reverse_slice(&mut buffer[..len]);
+            assert_eq!(buffer, expected, "seed={seed} len={len}");
+        }
+    }
+}
+
+#[test]
+fn should_validate_fisher_yates_shuffle_blocks() {
+    const SHUFFLE: FisherYates = FisherYates::with_seed(1);
+    const EXPECTED: [u8; 16] = *b"aaaabbbbccccdddd";
+
+    let mut buffer = EXPECTED;
+    SHUFFLE.shuffle_blocks(&mut buffer, 4);
+    assert_ne!(buffer, EXPECTED);
+    for block in buffer.chunks(4) {
+        assert!(block.iter().all(|byte| *byte == block[0]));
+        assert!(EXPECTED.chunks(4).any(|expected| expected == block));
+    }
+    SHUFFLE.reverse_blocks(&mut buffer, 4);
+    assert_eq!(buffer, EXPECTED);
+
+    let mut buffer = EXPECTED;
+    SHUFFLE.shuffle_blocks(&mut buffer, 1);
+    assert_eq!(buffer, SHUFFLE.shuffle_const(EXPECTED));
+    SHUFFLE.reverse_blocks(&mut buffer, 1);
+    assert_eq!(buffer, EXPECTED);
+
+    let mut buffer = EXPECTED;
+    SHUFFLE.shuffle_blocks(&mut buffer, 16);
+    assert_eq!(buffer, EXPECTED);
+    SHUFFLE.shuffle_blocks(&mut [], 4);
+    SHUFFLE.reverse_blocks(&mut [], 4);
+}
+
+#[test]
+fn should_validate_fisher_yates_shuffle_range() {
+    const SHUFFLE: FisherYates = FisherYates::with_seed(1);
+    const EXPECTED: [u8; 16] = *b"HEADpayload_TAIL";
+
+    let mut buffer = EXPECTED;
+    SHUFFLE.shuffle_range(&mut buffer, 4..12);
+    assert_eq!(buffer[..4], EXPECTED[..4]);
+    assert_eq!(buffer[12..], EXPECTED[12..]);
+    assert_ne!(buffer[4..12], EXPECTED[4..12]);
+    assert_eq!(buffer[4..12], SHUFFLE.shuffle_const(*b"payload_"));
+
+    SHUFFLE.reverse_range(&mut buffer, 4..12);
+    assert_eq!(buffer, EXPECTED);
+
+    SHUFFLE.shuffle_range(&mut buffer, 16..16);
+    SHUFFLE.shuffle_range(&mut buffer, 0..0);
+    assert_eq!(buffer, EXPECTED);
+}
+
+#[test]
+fn should_verify_secret_split_at() {
+    use obfus::{Secret, Binary};
+
+    let mut data = [0u8; 64];
+    for (idx, byte) in data.iter_mut().enumerate() {
+        *byte = idx as u8;
+    }
+
+    let secret = Secret::<64, Binary>::new(data);
+    let (left, right) = secret.split_at::<32, 32>();
+    assert_eq!(left.data(), &data[..32]);
+    assert_eq!(right.data(), &data[32..]);
+
+    let secret = Secret::<64, Binary>::new(data);
+    let (left, right) = secret.split_at::<0, 64>();
+    assert!(left.is_empty());
+    assert_eq!(right.data(), data);
+}
+
+#[test]
+fn should_verify_secret_concat() {
+    use obfus::{Secret, Binary, Text};
+
+    let mut data = [0u8; 64];
+    for (idx, byte) in data.iter_mut().enumerate() {
+        *byte = idx as u8;
+    }
+
+    let secret = Secret::<64, Binary>::new(data);
+    let (left, right) = secret.split_at::<16, 48>();
+    let secret = Secret::<64, Binary>::concat(left, right);
+    assert_eq!(secret.data(), data);
+
+    let left = Secret::<5, Text>::try_new(*b"hello").unwrap();
+    let right = Secret::<6, Binary>::new(*b" world");
+    let secret = Secret::<11, Binary>::concat(left, right);
+    assert_eq!(secret.data(), b"hello world");
+}
+
+#[test]
+fn should_verify_secret_expose() {
+    use core::mem::ManuallyDrop;
+    use obfus::{Secret, Binary};
+
+    let secret = Secret::<4, Binary>::new(*b"test");
+    let len = secret.expose(|data| {
+        assert_eq!(data, b"test");
+        data.len()
+    });
+    assert_eq!(len, 4);
+
+    let exposed = secret.into_exposed();
+    assert_eq!(&*exposed, b"test");
+    assert_eq!(exposed.as_ref(), b"test");
+
+    let mut exposed = ManuallyDrop::new(exposed);
+    unsafe {
+        core::ptr::drop_in_place(&mut *exposed);
+    }
+    assert_eq!(&**exposed, [0u8; 4]);
+}
+
+#[test]
+fn should_verify_secure_compare() {
+    assert!(secure_compare(b"", b""));
+    assert!(secure_compare(b"test", b"test"));
+    assert!(!secure_compare(b"test", b"tesT"));
+    assert!(!secure_compare(b"test", b"Test"));
+    assert!(!secure_compare(b"test", b"tes"));
+    assert!(!secure_compare(b"tes", b"test"));
+    assert!(!secure_compare(b"", b"test"));
+}
+
+#[test]
+fn should_verify_secure_zero() {
+    let mut buffer = [u32::MAX; 4];
+    secure_zero(&mut buffer);
+    assert_eq!(buffer, [0u32; 4]);
+
+    let mut buffer = [255u8; 15];
+    secure_zero(&mut buffer[..5]);
+    assert_eq!(buffer[..5], [0u8; 5]);
+    assert_eq!(buffer[5..], [255u8; 10]);
+}
+
+#[test]
+fn should_verify_secure_read() {
+    let mut buffer = [255u8; 15];
+    secure_memset(&mut buffer, 1);
+    assert_eq!(secure_read(&buffer), [1u8; 15]);
+    for byte in buffer.iter() {
+        assert_eq!(secure_read(byte), 1);
+    }
+
+    let value = 0xdead_beef_u32;
+    assert_eq!(secure_read(&value), value);
+}
+
+#[test]
+fn should_verify_secret_iter() {
+    use obfus::{Secret, Binary, Text};
+
+    let secret = Secret::<4, Binary>::new([1, 2, 3, 250]);
+    let sum: u64 = secret.iter().map(u64::from).sum();
+    assert_eq!(sum, 256);
+
+    let secret = Secret::<8, Text>::try_new(*b"t\xc3\xa9st\xc3\xa9 ").unwrap();
+    assert!(secret.chars().eq("tésté ".chars()));
+    assert_eq!(secret.iter().count(), 8);
+}
+
+#[cfg(feature = "subtle")]
+#[test]
+fn should_verify_secret_subtle_ct_eq() {
+    use obfus::subtle::ConstantTimeEq;
+    use obfus::{Secret, Binary};
+
+    let left = Secret::<4, Binary>::new(*b"test");
+    let right = Secret::<4, Binary>::new(*b"test");
+    assert!(bool::from(ConstantTimeEq::ct_eq(&left, &right)));
+
+    let right = Secret::<4, Binary>::new(*b"tesT");
+    assert!(!bool::from(ConstantTimeEq::ct_eq(&left, &right)));
+    assert!(bool::from(ConstantTimeEq::ct_ne(&left, &right)));
+}
+
+#[test]
+fn should_verify_secret_ct_eq_ignore_ascii_case() {
+    use obfus::{Secret, Text};
+
+    let secret = Secret::<6, Text>::try_new(*b"Secret").unwrap();
+    assert!(secret.ct_eq_ignore_ascii_case("secret"));
+    assert!(secret.ct_eq_ignore_ascii_case("SECRET"));
+    assert!(secret.ct_eq_ignore_ascii_case("Secret"));
+    assert!(!secret.ct_eq_ignore_ascii_case("secrxt"));
+    assert!(!secret.ct_eq_ignore_ascii_case("secre"));
+    assert!(!secret.ct_eq_ignore_ascii_case("secrets"));
+
+    let secret = Secret::<4, Text>::try_new(*b"\xc3\xa9a1").unwrap();
+    assert!(secret.ct_eq_ignore_ascii_case("éA1"));
+    assert!(!secret.ct_eq_ignore_ascii_case("ÉA1"));
+}
+
+#[test]
+fn should_verify_squares_reseed_and_rekey() {
+    use obfus::prng::Squares;
+
+    const KEY: u64 = 0x548c9decbce65297;
+
+    let mut prng = Squares::new(1);
+    prng.next();
+    prng.next();
+    prng.reseed(10);
+    let mut expected = Squares::new(10);
+    for _ in 0..4 {
+        assert_eq!(prng.next(), expected.next());
+    }
+
+    let mut prng = Squares::new(1);
+    prng.next();
+    prng.rekey(KEY);
+    let mut expected = Squares::with_key(KEY, 2);
+    for _ in 0..4 {
+        assert_eq!(prng.next(), expected.next());
+    }
+}
+
+#[test]
+fn should_verify_pcg32_known_answer() {
+    use obfus::prng::Pcg32;
+
+    //Reference output of pcg32-demo from pcg-c-basic
+    const EXPECTED: [u32; 6] = [0xa15c02b7, 0x7b47f409, 0xba1d3330, 0x83d2f293, 0xbfa4784b, 0xcbed606e];
+
+    let mut prng = Pcg32::with_stream(42, 54);
+    for expected in EXPECTED {
+        assert_eq!(prng.next(), expected);
+    }
+
+    let mut prng = Pcg32::with_stream(42, 54);
+    assert_eq!(prng.next_u64(), ((EXPECTED[1] as u64) << 32) | EXPECTED[0] as u64);
+
+    let mut left = Pcg32::new(42);
+    let mut right = Pcg32::new(42);
+    assert_eq!(left.next(), right.next());
+    assert_ne!(Pcg32::new(42).next(), Pcg32::new(43).next());
+}
+
+#[test]
+fn should_verify_squares_next_f64() {
+    use obfus::prng::Squares;
+
+    let mut prng = Squares::new(1);
+    let mut sum = 0.0;
+    for _ in 0..1000 {
+        let value = prng.next_f64();
+        assert!((0.0..1.0).contains(&value));
+        sum += value;
+    }
+    assert!((sum / 1000.0 - 0.5).abs() < 0.1);
+
+    for _ in 0..1000 {
+        assert!(!prng.next_bool_with(0.0));
+        assert!(prng.next_bool_with(1.0));
+    }
+}
+
+#[test]
+fn should_verify_buffer_resize() {
+    use aes_gcm::aead::Buffer;
+
+    let mut buffer = crypto::Buffer::<{crypto::required_buffer_size(10)}>::new();
+    buffer.resize(10, 1).expect("Success");
+    assert_eq!(buffer.data(), [1u8; 10]);
+
+    buffer.resize(27, 2).expect_err("Buffer overflow");
+    assert_eq!(buffer.data(), [1u8; 10]);
+
+    buffer.resize(26, 2).expect("Success");
+    assert_eq!(buffer.len(), 26);
+    assert_eq!(buffer.data()[10..], [2u8; 16]);
+
+    buffer.resize(4, 0).expect("Success");
+    assert_eq!(buffer.data(), [1u8; 4]);
+    buffer.resize(10, 3).expect("Success");
+    assert_eq!(buffer.data(), [1, 1, 1, 1, 3, 3, 3, 3, 3, 3]);
+
+    buffer.resize(0, 0).expect("Success");
+    assert!(buffer.is_empty());
+}
+
+#[test]
+fn should_verify_crypto128_api() {
+    use aes_gcm::aead::Buffer;
+
+    const DATA: &str = "data";
+    const BUFFER_SIZE: usize = crypto::required_buffer_size(DATA.len());
+    const NONCE: [u8; 12] = [2; 12];
+    type DataBuffer = crypto::Buffer<BUFFER_SIZE>;
+
+    let crypto = crypto::Crypto128::new([1; 16]);
+
+    let mut buffer = DataBuffer::new();
+    buffer.extend_from_slice(DATA.as_bytes()).expect("success");
+
+    crypto.encrypt(NONCE, &mut buffer).expect("to encrypt");
+    assert_eq!(buffer.len(), BUFFER_SIZE);
+    assert_ne!(&buffer.data()[..DATA.len()], DATA.as_bytes());
+
+    crypto.decrypt([0; 12], &mut buffer).expect_err("cannot decrypt with invalid nonce");
+    assert_eq!(buffer.len(), BUFFER_SIZE);
+
+    crypto.decrypt(NONCE, &mut buffer).expect("to decrypt");
+    assert_eq!(buffer.len(), DATA.len());
+    assert_eq!(buffer.data(), DATA.as_bytes());
+}
+
+#[cfg(feature = "siv")]
+#[test]
+fn should_verify_siv_crypto_api() {
+    use aes_gcm::aead::Buffer;
+
+    const DATA: &str = "data";
+    const BUFFER_SIZE: usize = crypto::required_buffer_size(DATA.len());
+    const NONCE: [u8; 12] = [2; 12];
+    type DataBuffer = crypto::Buffer<BUFFER_SIZE>;
+
+    let crypto = crypto::SivCrypto::new([1; 32]);
+
+    let mut buffer = DataBuffer::from_slice(DATA.as_bytes()).expect("success");
+    crypto.encrypt(NONCE, &mut buffer).expect("to encrypt");
+    assert_eq!(buffer.len(), BUFFER_SIZE);
+    assert_ne!(&buffer.data()[..DATA.len()], DATA.as_bytes());
+
+    //Nonce re-use produces the same ciphertext
+    let mut same_buffer = DataBuffer::from_slice(DATA.as_bytes()).expect("success");
+    crypto.encrypt(NONCE, &mut same_buffer).expect("to encrypt");
+    assert_eq!(buffer.data(), same_buffer.data());
+
+    crypto.decrypt([0; 12], &mut buffer).expect_err("cannot decrypt with invalid nonce");
+    assert_eq!(buffer.len(), BUFFER_SIZE);
+
+    crypto.decrypt(NONCE, &mut buffer).expect("to decrypt");
+    assert_eq!(buffer.len(), DATA.len());
+    assert_eq!(buffer.data(), DATA.as_bytes());
+}
+
+#[test]
+fn should_verify_crypto_drop() {
+    for idx in 0..4u8 {
+        let crypto = crypto::Crypto::new([idx; 32]);
+        let mut buffer = crypto::Buffer::<{crypto::required_buffer_size(4)}>::from_slice(b"data").expect("Success");
+        crypto.encrypt([idx; 12], &mut buffer).expect("to encrypt");
+        drop(crypto);
+
+        let crypto = crypto::Crypto::new([idx; 32]);
+        crypto.decrypt([idx; 12], &mut buffer).expect("to decrypt");
+        assert_eq!(buffer.data(), b"data");
+    }
+
+    drop(crypto::Crypto128::new([1; 16]));
+}
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn should_verify_crypto_zeroize_on_drop() {
+    use obfus::zeroize::ZeroizeOnDrop;
+
+    fn assert_zeroize_on_drop<T: ZeroizeOnDrop>(_: &T) {}
+
+    assert_zeroize_on_drop(&crypto::Crypto::new([1; 32]));
+    assert_zeroize_on_drop(&crypto::Crypto128::new([1; 16]));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn should_verify_serializable_secret() {
+    use obfus::{Secret, Binary, Text};
+    use obfus::storage::SerializableSecret;
+
+    const DATA: &str = "supersecretvalue";
+
+    let secret = Secret::<16, Text>::try_new(*b"supersecretvalue").unwrap();
+    let serializable = SerializableSecret::from_secret(&secret, 0x1234);
+    let serialized = serde_json::to_string(&serializable).expect("to serialize");
+    assert!(!serialized.contains(DATA));
+    let plain_bytes = serde_json::to_string(DATA.as_bytes()).expect("to serialize");
+    assert!(!serialized.contains(&plain_bytes[1..plain_bytes.len() - 1]));
+
+    let deserialized: SerializableSecret<16, Text> = serde_json::from_str(&serialized).expect("to deserialize");
+    assert_eq!(deserialized.get_secret().as_str(), DATA);
+    assert_eq!(deserialized.into_inner().get_secret().as_str(), DATA);
+
+    let deserialized: SerializableSecret<16, Binary> = serde_json::from_str(&serialized).expect("to deserialize");
+    assert_eq!(deserialized.get_secret().data(), DATA.as_bytes());
+
+    assert!(serde_json::from_str::<SerializableSecret<15, Binary>>(&serialized).is_err());
+    assert!(serde_json::from_str::<SerializableSecret<17, Binary>>(&serialized).is_err());
+
+    let invalid = SerializableSecret::from_secret(&Secret::<2, Binary>::new([255, 255]), 1);
+    let serialized = serde_json::to_string(&invalid).expect("to serialize");
+    assert!(serde_json::from_str::<SerializableSecret<2, Text>>(&serialized).is_err());
+}
+
+#[test]
+fn should_verify_base64_rfc4648_vectors() {
+    use obfus::utils::base64;
+
+    const VECTORS: [(&str, &str); 7] = [
+        ("", ""),
+        ("f", "Zg=="),
+        ("fo", "Zm8="),
+        ("foo", "Zm9v"),
+        ("foob", "Zm9vYg=="),
+        ("fooba", "Zm9vYmE="),
+        ("foobar", "Zm9vYmFy"),
+    ];
+
+    for (plain, encoded) in VECTORS {
+        let mut buffer = [0u8; 8];
+        assert_eq!(base64::encoded_len(plain.len()), encoded.len());
+        let len = base64::encode(&base64::STANDARD, plain.as_bytes(), &mut buffer).expect("to encode");
+        assert_eq!(&buffer[..len], encoded.as_bytes());
+
+        assert_eq!(base64::decoded_len(encoded.as_bytes()), Some(plain.len()));
+        let len = base64::decode(&base64::STANDARD, encoded.as_bytes(), &mut buffer).expect("to decode");
+        assert_eq!(&buffer[..len], plain.as_bytes());
+    }
+
+    const ENCODED: [u8; 8] = base64::encode_const(&base64::STANDARD, *b"foobar");
+    const DECODED: [u8; 5] = base64::decode_const(&base64::STANDARD, *b"Zm9vYmE=");
+    assert_eq!(&ENCODED, b"Zm9vYmFy");
+    assert_eq!(&DECODED, b"fooba");
+
+    let mut buffer = [0u8; 4];
+    assert_eq!(base64::encode(&base64::STANDARD, &[0xfb, 0xff], &mut buffer), Some(4));
+    assert_eq!(&buffer, b"+/8=");
+    assert_eq!(base64::encode(&base64::URL_SAFE, &[0xfb, 0xff], &mut buffer), Some(4));
+    assert_eq!(&buffer, b"-_8=");
+    assert_eq!(base64::decode(&base64::URL_SAFE, b"-_8=", &mut buffer), Some(2));
+    assert_eq!(buffer[..2], [0xfb, 0xff]);
+    assert_eq!(base64::decode(&base64::STANDARD, b"-_8=", &mut buffer), None);
+
+    //Invalid inputs
+    assert_eq!(base64::encode(&base64::STANDARD, b"foo", &mut [0u8; 3]), None);
+    assert_eq!(base64::decode(&base64::STANDARD, b"Zm9vYg==", &mut [0u8; 3]), None);
+    assert_eq!(base64::decode(&base64::STANDARD, b"Zm9", &mut buffer), None);
+    assert_eq!(base64::decode(&base64::STANDARD, b"Z===", &mut buffer), None);
+    assert_eq!(base64::decode(&base64::STANDARD, b"Zm=v", &mut buffer), None);
+    assert_eq!(base64::decode(&base64::STANDARD, b"Zg==Zg==", &mut buffer), None);
+    //Non-canonical trailing bits
+    assert_eq!(base64::decode(&base64::STANDARD, b"Zh==", &mut buffer), None);
+    assert_eq!(base64::decode(&base64::STANDARD, b"Zm9=", &mut buffer), None);
+    assert_eq!(base64::decode(&base64::STANDARD, b"Zm8=", &mut buffer), Some(2));
+}
+
+#[test]
+fn should_verify_hex_encoding() {
+    use obfus::utils::hex;
+
+    const ENCODED: [u8; 8] = hex::encode_const([0x00, 0x1f, 0xab, 0xff]);
+    const DECODED: [u8; 4] = hex::decode_const(*b"001FabfF");
+    assert_eq!(&ENCODED, b"001fabff");
+    assert_eq!(DECODED, [0x00, 0x1f, 0xab, 0xff]);
+
+    let mut buffer = [0u8; 8];
+    assert_eq!(hex::encode(b"", &mut buffer), Some(0));
+    assert_eq!(hex::encode(b"test", &mut buffer), Some(8));
+    assert_eq!(&buffer, b"74657374");
+    assert_eq!(hex::encode(b"test", &mut [0u8; 7]), None);
+
+    assert_eq!(hex::decode(b"74657374", &mut buffer), Some(4));
+    assert_eq!(&buffer[..4], b"test");
+    assert_eq!(hex::decode(b"", &mut buffer), Some(0));
+    assert_eq!(hex::decode(b"746", &mut buffer), None);
+    assert_eq!(hex::decode(b"7g", &mut buffer), None);
+    assert_eq!(hex::decode(b"g7", &mut buffer), None);
+    assert_eq!(hex::decode(b"7465", &mut [0u8; 1]), None);
+}
+
+#[test]
+fn should_verify_fisher_yates_reversible() {
+    const SHUFFLE: FisherYates = FisherYates::with_seed(1);
+    const _: () = assert!(SHUFFLE.verify_reversible::<32>());
+    const _: () = assert!(FisherYates::with_seed(u64::MAX).verify_reversible::<300>());
+
+    assert!(SHUFFLE.verify_reversible::<0>());
+    assert!(SHUFFLE.verify_reversible::<1>());
+    assert!(SHUFFLE.verify_reversible::<2>());
+    assert!(SHUFFLE.verify_reversible::<17>());
+    assert!(SHUFFLE.verify_reversible::<1024>());
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn should_validate_fisher_yates_reverse_property() {
+    use obfus::prng::Squares;
+
+    const PRIMES: [usize; 8] = [97, 251, 257, 509, 521, 1021, 2039, 4093];
+    const MAX_LEN: usize = 4096;
+
+    let mut expected = vec![0u16; MAX_LEN];
+    for (idx, value) in expected.iter_mut().enumerate() {
+        *value = idx as u16;
+    }
+
+    let mut seeds = Squares::new(0x5eed);
+    let mut lens = Squares::new(0x1e45);
+    for _ in 0..64 {
+        let seed = seeds.next();
+        let shuffle = FisherYates::with_seed(seed);
+
+        let random_len = lens.gen_range(MAX_LEN as u64 + 1) as usize;
+        for len in (0..=64).chain(PRIMES).chain([random_len, MAX_LEN]) {
+            let mut buffer = expected[..len].to_vec();
+            shuffle.shuffle_slice(&mut buffer);
+            shuffle.reverse_slice(&mut buffer);
+            assert_eq!(buffer, expected[..len], "seed={seed} len={len}");
+        }
+    }
+
+    for seed in [0, 1, u64::MAX / 2, u64::MAX - 64, u64::MAX - 1, u64::MAX] {
+        let shuffle = FisherYates::with_seed(seed);
+        for len in (0..=128).chain(PRIMES) {
+            let mut buffer = expected[..len].to_vec();
+            shuffle.shuffle_slice(&mut buffer);
+            shuffle.reverse_slice(&mut buffer);
+            assert_eq!(buffer, expected[..len], "seed={seed} len={len}");
+        }
+    }
+}
+
+#[test]
+fn should_verify_sealed_message() {
+    const KEY: [u8; 32] = [1; 32];
+    const NONCE: [u8; 12] = [2; 12];
+    const DATA: &[u8] = b"sealed";
+    const SIZE: usize = crypto::required_buffer_size(DATA.len());
+    type Message = crypto::SealedMessage<SIZE>;
+
+    let crypto = crypto::Crypto::new(KEY);
+    let message = Message::seal(&crypto, 1, NONCE, DATA).expect("to seal");
+    assert_eq!(message.version(), 1);
+    assert_eq!(*message.nonce(), NONCE);
+    assert_eq!(message.ciphertext().len(), SIZE);
+    assert_ne!(&message.ciphertext()[..DATA.len()], DATA);
+    assert_eq!(message.open(&crypto).expect("to open").data(), DATA);
+    assert!(Message::seal(&crypto, 1, NONCE, b"too long data").is_err());
+
+    let mut serialized = [0u8; crypto::SEALED_HEADER_SIZE + SIZE];
+    assert!(message.serialize(&mut serialized[1..]).is_err());
+    assert_eq!(message.serialize(&mut serialized).expect("to serialize"), message.serialized_len());
+    assert_eq!(serialized[0], 1);
+    assert_eq!(serialized[1..crypto::SEALED_HEADER_SIZE], NONCE);
+
+    let parsed = Message::parse(&serialized).expect("to parse");
+    assert_eq!(parsed.open(&crypto).expect("to open").data(), DATA);
+    assert!(Message::parse(&serialized[..crypto::SEALED_HEADER_SIZE + crypto::TAG_SIZE - 1]).is_err());
+    assert!(crypto::SealedMessage::<{SIZE - 1}>::parse(&serialized).is_err());
+
+    //Downgrade attempt
+    serialized[0] = 0;
+    let downgraded = Message::parse(&serialized).expect("to parse");
+    assert_eq!(downgraded.version(), 0);
+    assert!(downgraded.open(&crypto).is_err());
+}
+
+#[test]
+fn should_verify_secret_builder() {
+    use obfus::SecretBuilder;
+
+    let key: [u8; 32] = core::array::from_fn(|idx| idx as u8);
+
+    let mut builder = SecretBuilder::<32>::new();
+    assert!(builder.is_empty());
+    for chunk in key.chunks(8) {
+        builder.push_slice(chunk).expect("to push");
+    }
+    assert_eq!(builder.len(), 32);
+    assert_eq!(builder.remaining(), 0);
+    assert!(builder.push_slice(&[1]).is_err());
+    assert_eq!(format!("{builder:?}"), "SecretBuilder<32>(32/32 [REDACTED])");
+    let secret = builder.finish().expect("to finish");
+    assert_eq!(secret.data(), key);
+
+    let mut builder = SecretBuilder::<32>::new();
+    builder.push_slice(&key[..24]).expect("to push");
+    assert!(builder.push_slice(&key[..16]).is_err());
+    assert_eq!(builder.len(), 24);
+    assert!(builder.finish().is_none());
+
+    let builder = SecretBuilder::<0>::default();
+    assert!(builder.finish().expect("to finish").is_empty());
+}
+
+#[test]
+fn should_verify_secret_conditional_select() {
+    use obfus::{Secret, Binary, Text};
+
+    //Every byte differs, so result must consult both inputs at every position
+    let a = Secret::<16, Binary>::new(core::array::from_fn(|idx| idx as u8));
+    let b = Secret::<16, Binary>::new(core::array::from_fn(|idx| !(idx as u8)));
+
+    let selected = Secret::conditional_select(&a, &b, false);
+    assert_eq!(selected.data(), a.data());
+    let selected = Secret::conditional_select(&a, &b, true);
+    assert_eq!(selected.data(), b.data());
+
+    let a = Secret::<4, Text>::try_new(*b"left").expect("utf-8");
+    let b = Secret::<4, Text>::try_new(*b"rght").expect("utf-8");
+    assert_eq!(Secret::conditional_select(&a, &b, false).as_str(), "left");
+    assert_eq!(Secret::conditional_select(&a, &b, true).as_str(), "rght");
+
+    #[cfg(feature = "subtle")]
+    {
+        assert_eq!(Secret::conditional_select_choice(&a, &b, obfus::subtle::Choice::from(0)).as_str(), "left");
+        assert_eq!(Secret::conditional_select_choice(&a, &b, obfus::subtle::Choice::from(1)).as_str(), "rght");
+    }
+}
+
+#[test]
+fn should_verify_fisher_yates_obfuscate() {
+    fn histogram(data: &[u8]) -> [usize; 256] {
+        let mut result = [0; 256];
+        for byte in data {
+            result[*byte as usize] += 1;
+        }
+        result
+    }
+
+    const SHUFFLE: FisherYates = FisherYates::with_seed(0x1234);
+    const DATA: &[u8; 43] = b"the quick brown fox jumps over the lazy dog";
+    const OBFUSCATED: [u8; 43] = SHUFFLE.obfuscate_const(*DATA);
+    const DEOBFUSCATED: [u8; 43] = SHUFFLE.deobfuscate_const(OBFUSCATED);
+
+    assert_eq!(DEOBFUSCATED, *DATA);
+    assert_ne!(histogram(&OBFUSCATED), histogram(DATA));
+    //Pure shuffle preserves histogram
+    assert_eq!(histogram(&SHUFFLE.shuffle_const(*DATA)), histogram(DATA));
+
+    for seed in [0, 1, u64::MAX] {
+        let shuffle = FisherYates::with_seed(seed);
+        let mut data = *DATA;
+        shuffle.obfuscate(&mut data);
+        assert_ne!(histogram(&data), histogram(DATA));
+        shuffle.deobfuscate(&mut data);
+        assert_eq!(data, *DATA);
+    }
+
+    let mut empty: [u8; 0] = [];
+    SHUFFLE.obfuscate(&mut empty);
+    SHUFFLE.deobfuscate(&mut empty);
+}
+
+#[test]
+fn should_verify_cipher_trait() {
+    use aes_gcm::aead::Buffer;
+    use crypto::Cipher;
+
+    const DATA: &[u8] = b"generic";
+    type DataBuffer = crypto::Buffer<{crypto::required_buffer_size(DATA.len())}>;
+
+    fn roundtrip<C: Cipher>(cipher: &C, nonce: C::Nonce) {
+        assert_eq!(nonce.as_ref().len(), C::NONCE_SIZE);
+
+        let mut buffer = DataBuffer::new();
+        buffer.extend_from_slice(DATA).expect("Success");
+        cipher.encrypt(nonce, &mut buffer).expect("to encrypt");
+        assert_ne!(&buffer.data()[..DATA.len()], DATA);
+        cipher.decrypt(nonce, &mut buffer).expect("to decrypt");
+        assert_eq!(buffer.data(), DATA);
+
+        cipher.encrypt_with_aad(nonce, b"aad", &mut buffer).expect("to encrypt");
+        cipher.decrypt_with_aad(nonce, b"other", &mut buffer).expect_err("cannot decrypt with wrong aad");
+        cipher.decrypt_with_aad(nonce, b"aad", &mut buffer).expect("to decrypt");
+        assert_eq!(buffer.data(), DATA);
+    }
+
+    assert_eq!(crypto::Crypto::NONCE_SIZE, 12);
+    roundtrip(&crypto::Crypto::new([1; 32]), [2; 12]);
+    roundtrip(&crypto::Crypto128::new([1; 16]), [2; 12]);
+
+    #[cfg(feature = "chacha20poly1305")]
+    {
+        assert_eq!(crypto::XChaChaCrypto::NONCE_SIZE, 24);
+        roundtrip(&crypto::ChaChaCrypto::new([1; 32]), [2; 12]);
+        roundtrip(&crypto::XChaChaCrypto::new([1; 32]), [2; 24]);
+    }
+
+    #[cfg(feature = "siv")]
+    roundtrip(&crypto::SivCrypto::new([1; 32]), [2; 12]);
+}
+
+#[test]
+fn should_verify_buffer_index() {
+    let buffer = crypto::Buffer::<20>::from_slice(b"test").expect("to create");
+    assert_eq!(buffer[0], b't');
+    assert_eq!(buffer[3], b't');
+    assert_eq!(&buffer[0..4], b"test");
+    assert_eq!(&buffer[1..3], b"es");
+    assert_eq!(&buffer[2..], b"st");
+    assert_eq!(&buffer[..], b"test");
+}
+
+#[test]
+fn should_compare_buffers() {
+    let buffer = crypto::Buffer::<20>::from_slice(b"test").expect("to create");
+    assert_eq!(buffer, b"test");
+    assert_eq!(buffer, *b"test");
+    assert_eq!(buffer, b"test".as_slice());
+    assert_eq!(buffer, *b"test".as_slice());
+    assert_ne!(buffer, b"tost");
+    assert_ne!(buffer, b"tes");
+    assert_ne!(buffer, b"test1");
+    assert_ne!(buffer, b"".as_slice());
+
+    assert_eq!(buffer, crypto::Buffer::<20>::from_slice(b"test").expect("to create"));
+    assert_ne!(buffer, crypto::Buffer::<20>::from_slice(b"tes").expect("to create"));
+    assert_eq!(crypto::Buffer::<20>::new(), crypto::Buffer::<20>::new());
+    assert_eq!(format!("{buffer:?}"), "Buffer<20>(4 bytes)");
+}
+
+#[test]
+#[should_panic]
+fn should_panic_on_buffer_index_out_of_written_region() {
+    let buffer = crypto::Buffer::<20>::from_slice(b"test").expect("to create");
+    let _ = buffer[4];
+}
+
+#[test]
+#[should_panic]
+fn should_panic_on_buffer_range_out_of_written_region() {
+    let buffer = crypto::Buffer::<20>::from_slice(b"test").expect("to create");
+    let _ = &buffer[2..5];
+}
+
+#[test]
+fn should_reject_weak_squares_keys() {
+    use obfus::prng::Squares;
+
+    const GOOD_KEY: u64 = 0x7d8b63f54b86ca59;
+
+    for key in [0, u64::MAX, 1, 0xFFFFFFFF, 0xFFFFFFFF00000000, 0x8000000000000000] {
+        assert!(Squares::is_weak_key(key), "{key:#x} should be weak");
+        assert!(Squares::with_key_checked(key, 0).is_none());
+    }
+
+    assert!(!Squares::is_weak_key(GOOD_KEY));
+    let mut checked = Squares::with_key_checked(GOOD_KEY, 5).expect("good key");
+    let mut expected = Squares::new(5);
+    assert_eq!(checked.next(), expected.next());
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn should_verify_fisher_yates_chunked_swap_indexes() {
+    use obfus::prng::Squares;
+
+    const LEN: usize = 1000;
+    const CHUNK: usize = 128;
+
+    for seed in [0, 1, 0x1234, u64::MAX] {
+        let shuffle = FisherYates::with_seed(seed);
+        assert_eq!(shuffle.prng_at(10).next(), Squares::new(seed.wrapping_add(10)).next());
+
+        let original: [u16; LEN] = core::array::from_fn(|idx| idx as u16);
+        let mut shuffled = original;
+        shuffle.shuffle_slice(&mut shuffled);
+
+        let mut indexes = [0usize; LEN];
+        std::thread::scope(|scope| {
+            for (chunk_idx, chunk) in indexes.chunks_mut(CHUNK).enumerate() {
+                let shuffle = &shuffle;
+                scope.spawn(move || shuffle.swap_indexes(chunk_idx * CHUNK, LEN, chunk));
+            }
+        });
+
+        let mut expected = shuffled;
+        shuffle.reverse_slice(&mut expected);
+        assert_eq!(expected, original);
+
+        let mut reversed = shuffled;
+        FisherYates::reverse_with_indexes(&mut reversed, &indexes);
+        assert_eq!(reversed, expected);
+
+        let mut reshuffled = original;
+        FisherYates::shuffle_with_indexes(&mut reshuffled, &indexes);
+        assert_eq!(reshuffled, shuffled);
+    }
+}
+
+#[test]
+fn should_verify_buffer_plaintext_len() {
+    const DATA: &[u8] = b"framed message";
+
+    let crypto = crypto::Crypto::new([1; 32]);
+    let mut buffer = crypto::Buffer::<{crypto::required_buffer_size(32)}>::from_slice(DATA).expect("to create");
+    assert_eq!(buffer.plaintext_len(), None);
+
+    crypto.encrypt([2; 12], &mut buffer).expect("to encrypt");
+    assert_eq!(buffer.plaintext_len(), Some(DATA.len()));
+    crypto.decrypt([2; 12], &mut buffer).expect("to decrypt");
+    assert_eq!(buffer.data(), DATA);
+
+    let mut buffer = crypto::Buffer::<{crypto::required_buffer_size(32)}>::new();
+    crypto.encrypt([2; 12], &mut buffer).expect("to encrypt");
+    assert_eq!(buffer.plaintext_len(), Some(0));
+}
+
+#[test]
+fn should_verify_secret_text_macro() {
+    use obfus::{Secret, Text};
+
+    let secret: Secret<5, Text> = obfus::secret_text!("hello");
+    assert_eq!(secret.as_str(), "hello");
+
+    //Length is in bytes, not characters
+    let secret: Secret<6, Text> = obfus::secret_text!("héllo");
+    assert_eq!(secret.as_str(), "héllo");
+    assert_eq!(secret.chars().count(), 5);
+
+    let secret: Secret<0, Text> = obfus::secret_text!("",);
+    assert!(secret.is_empty());
+}
+
+#[test]
+fn should_verify_secret_prefix_masked() {
+    use obfus::{Secret, Text};
+
+    let secret: Secret<12, Text> = obfus::secret_text!("sk-abcdefghi");
+    let mut out = [0u8; 32];
+    assert_eq!(secret.prefix_masked(5, '*', &mut out), Some("sk-ab*******"));
+    assert_eq!(secret.prefix_masked(0, '*', &mut out), Some("************"));
+    assert_eq!(secret.prefix_masked(100, '*', &mut out), Some("sk-abcdefghi"));
+    assert_eq!(secret.prefix_masked(3, '•', &mut out), Some("sk-•••••••••"));
+    assert_eq!(secret.prefix_masked(3, '*', &mut out[..11]), None);
+
+    //Emoji takes 4 bytes, and must never be split
+    let secret: Secret<11, Text> = obfus::secret_text!("a🔑b🔒c");
+    for visible in 0..=6 {
+        let result = secret.prefix_masked(visible, '*', &mut out).expect("to mask");
+        assert_eq!(result.chars().count(), 5);
+        assert!(secret.as_str().starts_with(result.trim_end_matches('*')));
+    }
+    assert_eq!(secret.prefix_masked(2, '*', &mut out), Some("a🔑***"));
+    assert_eq!(secret.prefix_masked(4, '*', &mut out), Some("a🔑b🔒*"));
+}
+
+#[cfg(feature = "mlock")]
+#[cfg_attr(miri, ignore)]
+#[test]
+fn should_verify_locked_secret() {
+    use obfus::{Secret, Binary, Text};
+    use obfus::locked::LockedSecret;
+
+    let secret = Secret::<4, Binary>::new(*b"test");
+    let locked = LockedSecret::new(secret).expect("to lock");
+    assert_eq!(locked.len(), 4);
+    assert_eq!(locked.data(), b"test");
+    assert_eq!(format!("{locked:?}"), "LockedSecret<4>([REDACTED])");
+    drop(locked);
+
+    let locked = LockedSecret::new(obfus::secret_text!("locked text")).expect("to lock");
+    assert_eq!(locked.as_str(), "locked text");
+
+    let locked = LockedSecret::new(Secret::<0, Text>::try_new([]).expect("utf-8")).expect("to lock");
+    assert!(locked.is_empty());
+    assert_eq!(locked.as_str(), "");
+}
+
+#[cfg(feature = "hash")]
+#[test]
+fn should_verify_hashed_secret() {
+    use obfus::storage::HashedSecret;
+
+    //sha256("saltpassword")
+    const EXPECTED: [u8; 32] = [
+        0x13, 0x60, 0x1b, 0xda, 0x4e, 0xa7, 0x8e, 0x55, 0xa0, 0x7b, 0x98, 0x86, 0x6d, 0x2b, 0xe6, 0xbe,
+        0x07, 0x44, 0xe3, 0x86, 0x6f, 0x13, 0xc0, 0x0c, 0x81, 0x1c, 0xab, 0x60, 0x8a, 0x28, 0xf3, 0x22,
+    ];
+
+    let hashed = HashedSecret::new(obfus::secret_text!("password"), *b"salt");
+    assert_eq!(*hashed.salt(), *b"salt");
+    assert_eq!(*hashed.digest(), EXPECTED);
+    assert!(hashed.verify(b"password"));
+    assert!(!hashed.verify(b"Password"));
+    assert!(!hashed.verify(b"password "));
+    assert!(!hashed.verify(b""));
+
+    let other = HashedSecret::new(obfus::secret_text!("password"), *b"pepper");
+    assert_ne!(other.digest(), hashed.digest());
+    assert!(other.verify(b"password"));
+}
+
+#[test]
+fn should_verify_squares_checkpoint() {
+    use obfus::prng::Squares;
+
+    let mut prng = Squares::new(0x1234);
+    for _ in 0..3 {
+        prng.next();
+    }
+    let (key, counter) = prng.state();
+    assert_eq!(counter, 0x1234 + 3);
+
+    let mut restored = Squares::from_state(key, counter);
+    for _ in 0..10 {
+        assert_eq!(restored.next(), prng.next());
+    }
+    assert_eq!(restored.state(), prng.state());
+}
+
+#[test]
+fn should_verify_squares_split() {
+    use obfus::prng::Squares;
+
+    let parent = Squares::new(0x1234);
+    let mut first = parent.split(0);
+    let mut second = parent.split(1);
+    let mut parent_stream = Squares::new(0x1234);
+
+    let first = core::array::from_fn::<u64, 1000, _>(|_| first.next());
+    let second = core::array::from_fn::<u64, 1000, _>(|_| second.next());
+    let parent_stream = core::array::from_fn::<u64, 1000, _>(|_| parent_stream.next());
+    assert!(first.iter().zip(second.iter()).all(|(left, right)| left != right));
+    assert!(first.iter().zip(parent_stream.iter()).all(|(left, right)| left != right));
+    assert!(first.iter().all(|value| !second.contains(value)));
+
+    //Derivation is deterministic
+    let mut again = parent.split(0);
+    assert_eq!(again.next(), first[0]);
+}
+
+#[test]
+fn should_verify_shuffled_const_macro() {
+    const SHUFFLED: [u8; 6] = obfus::shuffled_const!(seed = 1, b"secret");
+    const EMPTY: [u8; 0] = obfus::shuffled_const!(seed = 1, b"",);
+
+    assert_ne!(SHUFFLED, *b"secret");
+    assert_eq!(SHUFFLED, FisherYates::with_seed(1).shuffle_const(*b"secret"));
+    assert_eq!(FisherYates::with_seed(1).reverse_const(SHUFFLED), *b"secret");
+    assert_eq!(EMPTY, [0u8; 0]);
+
+    let shuffled = obfus::shuffled_const!(seed = 0x1234, b"\x00\x01\x02\x03\x04\x05\x06\x07");
+    assert_eq!(FisherYates::with_seed(0x1234).reverse_const(shuffled), [0, 1, 2, 3, 4, 5, 6, 7]);
+}
+
+#[cfg(feature = "hash")]
+#[test]
+fn should_verify_committing_crypto() {
+    use aes_gcm::aead::Buffer;
+
+    const DATA: &[u8] = b"committed";
+    const NONCE: [u8; 12] = [2; 12];
+    type DataBuffer = crypto::Buffer<{crypto::required_committing_buffer_size(DATA.len())}>;
+
+    let committing = crypto::CommittingCrypto::new([1; 32]);
+    let other = crypto::CommittingCrypto::new([3; 32]);
+
+    let mut buffer = DataBuffer::from_slice(DATA).expect("to create");
+    committing.encrypt_committing(NONCE, &mut buffer).expect("to encrypt");
+    assert_eq!(buffer.len(), crypto::required_committing_buffer_size(DATA.len()));
+    assert!(committing.verify_commitment(NONCE, &buffer));
+    assert_ne!(&buffer[crypto::COMMITMENT_SIZE..crypto::COMMITMENT_SIZE + DATA.len()], DATA);
+
+    //Different key is rejected by commitment check, before tag is verified
+    assert!(!other.verify_commitment(NONCE, &buffer));
+    assert!(other.decrypt_committing(NONCE, &mut buffer).is_err());
+    //Different nonce is rejected too
+    assert!(!committing.verify_commitment([0; 12], &buffer));
+    assert!(committing.decrypt_committing([0; 12], &mut buffer).is_err());
+
+    committing.decrypt_committing(NONCE, &mut buffer).expect("to decrypt");
+    assert_eq!(buffer.data(), DATA);
+
+    //Tampered commitment is rejected
+    committing.encrypt_committing(NONCE, &mut buffer).expect("to encrypt");
+    buffer.data_mut()[0] ^= 1;
+    assert!(committing.decrypt_committing(NONCE, &mut buffer).is_err());
+
+    //No capacity for commitment
+    let mut buffer = crypto::Buffer::<{crypto::required_buffer_size(DATA.len())}>::from_slice(DATA).expect("to create");
+    assert!(committing.encrypt_committing(NONCE, &mut buffer).is_err());
+    assert_eq!(buffer.data(), DATA);
+
+    let mut buffer = DataBuffer::new();
+    buffer.extend_from_slice(&[0; crypto::COMMITMENT_SIZE]).expect("to extend");
+    assert!(committing.decrypt_committing(NONCE, &mut buffer).is_err());
+    //Buffer too small to ever hold commitment and tag
+    let mut buffer = crypto::Buffer::<20>::new();
+    assert!(committing.encrypt_committing(NONCE, &mut buffer).is_err());
+    assert!(committing.decrypt_committing(NONCE, &mut buffer).is_err());
+    let mut buffer = crypto::Buffer::<20>::from_slice(b"test").expect("to create");
+    assert!(committing.encrypt_committing(NONCE, &mut buffer).is_err());
+    assert_eq!(buffer, b"test");
+}
+
+#[test]
+fn should_verify_secret_into_text() {
+    use obfus::{Secret, Binary};
+
+    let secret = Secret::<6, Binary>::new("héllo".as_bytes().try_into().expect("6 bytes"));
+    let text = secret.into_text().expect("valid utf-8");
+    assert_eq!(text.as_str(), "héllo");
+
+    let secret = Secret::<4, Binary>::new([0xff, b'a', b'b', b'c']);
+    let secret = secret.into_text().expect_err("invalid utf-8");
+    assert_eq!(secret.data(), [0xff, b'a', b'b', b'c']);
+
+    //Truncated multibyte sequence
+    let secret = Secret::<2, Binary>::new([b'h', 0xc3]);
+    assert!(secret.into_text().is_err());
+
+    let secret = Secret::<0, Binary>::new([]);
+    assert_eq!(secret.into_text().expect("valid utf-8").as_str(), "");
+}
+
+#[test]
+fn should_verify_crypto_encrypt_batch() {
+    const DATA: [&[u8]; 3] = [b"first", b"second", b"third"];
+    type DataBuffer = crypto::Buffer<{crypto::required_buffer_size(6)}>;
+
+    let crypto = crypto::Crypto::new([1; 32]);
+    let mut nonces = crypto::NonceSequence::with_prefix([1, 2, 3, 4]);
+    let mut buffers = DATA.map(|data| DataBuffer::from_slice(data).expect("to create"));
+    crypto.encrypt_batch(&mut nonces, &mut buffers).expect("to encrypt");
+    assert_eq!(nonces.counter(), 3);
+
+    let mut nonces = crypto::NonceSequence::with_prefix([1, 2, 3, 4]);
+    for (buffer, data) in buffers.iter_mut().zip(DATA) {
+        assert_ne!(&buffer.data()[..data.len()], data);
+        crypto.decrypt(nonces.next().expect("nonce"), buffer).expect("to decrypt");
+        assert_eq!(buffer.data(), data);
+    }
+
+    //Exhausted nonces
+    let mut nonces = crypto::NonceSequence::with_counter([0; 4], u64::MAX - 2);
+    assert_eq!(crypto.encrypt_batch(&mut nonces, &mut buffers), Err(2));
+    assert_eq!(buffers[2].data(), DATA[2]);
+
+    //Buffer without capacity for tag
+    let mut buffers = [DataBuffer::from_slice(b"full").expect("to create"), DataBuffer::new()];
+    buffers[1].resize(crypto::required_buffer_size(6), 0).expect("to resize");
+    let mut nonces = crypto::NonceSequence::new();
+    assert_eq!(crypto.encrypt_batch(&mut nonces, &mut buffers), Err(1));
+}
+
+#[cfg(feature = "hkdf")]
+#[test]
+fn should_verify_secret_hkdf_expand() {
+    use obfus::{Secret, Binary};
+
+    //RFC 5869 test case 1
+    const EXPECTED: [u8; 42] = [
+        0x3c, 0xb2, 0x5f, 0x25, 0xfa, 0xac, 0xd5, 0x7a, 0x90, 0x43, 0x4f, 0x64, 0xd0, 0x36, 0x2f, 0x2a,
+        0x2d, 0x2d, 0x0a, 0x90, 0xcf, 0x1a, 0x5a, 0x4c, 0x5d, 0xb0, 0x2d, 0x56, 0xec, 0xc4, 0xc5, 0xbf,
+        0x34, 0x00, 0x72, 0x08, 0xd5, 0xb8, 0x87, 0x18, 0x58, 0x65,
+    ];
+    const SALT: [u8; 13] = [0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c];
+    const INFO: [u8; 10] = [0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7, 0xf8, 0xf9];
+
+    let ikm = Secret::<22, Binary>::new([0x0b; 22]);
+    let okm = ikm.hkdf_expand::<42>(&SALT, &INFO);
+    assert_eq!(okm.data(), EXPECTED);
+
+    let master = Secret::<32, Binary>::new([1; 32]);
+    let encryption = master.hkdf_expand::<32>(b"salt", b"encryption");
+    let authentication = master.hkdf_expand::<32>(b"salt", b"authentication");
+    assert_ne!(encryption.data(), authentication.data());
+    assert_ne!(encryption.data(), master.data());
+    assert_eq!(master.hkdf_expand::<32>(b"salt", b"encryption").data(), encryption.data());
+}
+
+#[test]
+fn should_verify_fisher_yates_permutation() {
+    const SHUFFLE: FisherYates = FisherYates::with_seed(0x1234);
+    const PERMUTATION: [usize; 32] = SHUFFLE.permutation::<32>();
+    const INVERSE: [usize; 32] = SHUFFLE.inverse_permutation::<32>();
+
+    let original: [u8; 32] = core::array::from_fn(|idx| idx as u8);
+    let shuffled = SHUFFLE.shuffle_const(original);
+
+    let applied: [u8; 32] = core::array::from_fn(|idx| original[PERMUTATION[idx]]);
+    assert_eq!(applied, shuffled);
+    assert_ne!(applied, original);
+
+    let restored: [u8; 32] = core::array::from_fn(|idx| shuffled[INVERSE[idx]]);
+    assert_eq!(restored, original);
+
+    //Parallel array follows the same order
+    let labels: [char; 32] = core::array::from_fn(|idx| (b'A' + idx as u8) as char);
+    let mut shuffled_labels = labels;
+    SHUFFLE.shuffle_slice(&mut shuffled_labels);
+    let applied: [char; 32] = core::array::from_fn(|idx| labels[PERMUTATION[idx]]);
+    assert_eq!(applied, shuffled_labels);
+
+    assert_eq!(SHUFFLE.permutation::<0>(), [0usize; 0]);
+    assert_eq!(SHUFFLE.permutation::<1>(), [0]);
+}
+
+#[test]
+fn should_verify_secret_try_as_str() {
+    use obfus::{Secret, Text};
+
+    let secret: Secret<5, Text> = obfus::secret_text!("héll");
+    assert_eq!(secret.try_as_str(), Ok("héll"));
+
+    //Deliberately violates Text invariant
+    let corrupted = unsafe {
+        Secret::<4, Text>::new([b'a', 0xff, b'b', b'c'])
+    };
+    let error = corrupted.try_as_str().expect_err("invalid utf-8");
+    assert_eq!(error.valid_up_to(), 1);
+}
+
+#[test]
+fn should_verify_buffer_push_and_extend_from_iter() {
+    let mut buffer = crypto::Buffer::<{crypto::required_buffer_size(4)}>::new();
+    for byte in *b"test" {
+        buffer.push(byte).expect("to push");
+    }
+    assert_eq!(buffer.data(), b"test");
+    assert!(buffer.push(b'!').is_err());
+    assert_eq!(buffer.data(), b"test");
+
+    let mut buffer = crypto::Buffer::<{crypto::required_buffer_size(4)}>::new();
+    buffer.extend_from_iter(*b"te").expect("to extend");
+    buffer.extend_from_iter(core::iter::empty()).expect("to extend");
+    assert_eq!(buffer.data(), b"te");
+
+    //Overflow restores original state
+    assert!(buffer.extend_from_iter(*b"sts").is_err());
+    assert_eq!(buffer.data(), b"te");
+    assert!(buffer.extend_from_iter(core::iter::repeat(1)).is_err());
+    assert_eq!(buffer.data(), b"te");
+
+    buffer.extend_from_iter(b"st".iter().copied()).expect("to extend");
+    assert_eq!(buffer.data(), b"test");
+
+    //Filled buffer is still suitable for encryption
+    let crypto = crypto::Crypto::new([1; 32]);
+    crypto.encrypt([2; 12], &mut buffer).expect("to encrypt");
+    crypto.decrypt([2; 12], &mut buffer).expect("to decrypt");
+    assert_eq!(buffer.data(), b"test");
+}
+
+#[test]
+fn should_zero_buffer_on_failed_decrypt_ct() {
+    const DATA: &[u8] = b"constant time";
+    type DataBuffer = crypto::Buffer<{crypto::required_buffer_size(DATA.len())}>;
+
+    let crypto = crypto::Crypto::new([1; 32]);
+    let mut buffer = DataBuffer::from_slice(DATA).expect("to create");
+    crypto.encrypt([2; 12], &mut buffer).expect("to encrypt");
+    let len = buffer.data().len();
+
+    let mut valid = DataBuffer::new();
+    valid.resize(len, 0).expect("to resize");
+    valid.data_mut().copy_from_slice(buffer.data());
+    crypto.decrypt_ct([2; 12], &mut valid).expect("to decrypt");
+    assert_eq!(valid.data(), DATA);
+
+    assert!(crypto.decrypt_ct([3; 12], &mut buffer).is_err());
+    assert_eq!(buffer.data(), [0u8; crypto::required_buffer_size(DATA.len())]);
+
+    #[cfg(feature = "chacha20poly1305")]
+    {
+        let crypto = crypto::ChaChaCrypto::new([1; 32]);
+        let mut buffer = DataBuffer::from_slice(DATA).expect("to create");
+        crypto.encrypt([2; 12], &mut buffer).expect("to encrypt");
+        buffer.data_mut()[0] ^= 1;
+        assert!(crypto.decrypt_ct([2; 12], &mut buffer).is_err());
+        assert_eq!(buffer.data(), [0u8; crypto::required_buffer_size(DATA.len())]);
+    }
+}
+
+#[test]
+fn should_zero_buffer_on_failed_encrypt() {
+    let crypto = crypto::Crypto::new([1; 32]);
+
+    //No space left for tag
+    let mut buffer = crypto::Buffer::<{crypto::required_buffer_size(4)}>::new();
+    buffer.resize(crypto::required_buffer_size(4), b'x').expect("to resize");
+    assert!(crypto.encrypt([2; 12], &mut buffer).is_err());
+    assert_eq!(buffer.data(), [0u8; crypto::required_buffer_size(4)]);
+
+    let mut buffer = crypto::Buffer::<{crypto::required_buffer_size(4)}>::new();
+    buffer.resize(crypto::required_buffer_size(4) - 1, b'x').expect("to resize");
+    assert!(crypto.encrypt_with_aad([2; 12], b"aad", &mut buffer).is_err());
+    assert_eq!(buffer.data(), [0u8; crypto::required_buffer_size(4) - 1]);
+
+    #[cfg(feature = "chacha20poly1305")]
+    {
+        let crypto = crypto::ChaChaCrypto::new([1; 32]);
+        let mut buffer = crypto::Buffer::<{crypto::required_buffer_size(4)}>::new();
+        buffer.resize(crypto::required_buffer_size(4), b'x').expect("to resize");
+        assert!(crypto.encrypt([2; 12], &mut buffer).is_err());
+        assert_eq!(buffer.data(), [0u8; crypto::required_buffer_size(4)]);
+    }
+}
+
+#[test]
+fn should_verify_var_secret() {
+    use core::mem::ManuallyDrop;
+    use obfus::{VarSecret, Binary, Text};
+
+    let secret = VarSecret::<64, Binary>::new(b"token").expect("to fit");
+    assert_eq!(secret.len(), 5);
+    assert_eq!(secret.capacity(), 64);
+    assert_eq!(secret.data(), b"token");
+    assert!(secret.ct_eq(b"token"));
+    assert!(!secret.ct_eq(b"tokem"));
+    assert_eq!(format!("{secret:?}"), "VarSecret<64>([REDACTED])");
+
+    let secret = VarSecret::<8, Text>::new("héllo").expect("to fit");
+    assert_eq!(secret.as_str(), "héllo");
+    assert_eq!(secret.len(), 6);
+
+    assert!(VarSecret::<4, Binary>::new(b"token").is_none());
+    assert!(VarSecret::<4, Text>::new("héllo").is_none());
+    assert!(VarSecret::<4, Binary>::new(b"").expect("to fit").is_empty());
+    assert_eq!(VarSecret::<4, Binary>::new(b"full").expect("to fit").data(), b"full");
+
+    let mut secret = ManuallyDrop::new(VarSecret::<8, Binary>::new(&[0xff; 8]).expect("to fit"));
+    unsafe {
+        core::ptr::drop_in_place(&mut *secret);
+    }
+    assert_eq!(secret.data(), [0u8; 8]);
+}
+
+#[test]
+fn should_verify_xor_keystream_at_offset() {
+    use obfus::xor::{apply_keystream_at, XorCipher};
+
+    const SEED: u64 = 0x1234;
+    let original: [u8; 64] = core::array::from_fn(|idx| idx as u8);
+    let mut full = original;
+    XorCipher::with_seed(SEED).obfuscate(&mut full);
+
+    for offset in [0, 1, 5, 7, 8, 9, 16, 33, 63] {
+        for len in [0, 1, 3, 8, 13] {
+            let end = (offset + len).min(original.len());
+            let mut chunk = original;
+            let chunk = &mut chunk[offset..end];
+            apply_keystream_at(SEED, offset, chunk);
+            assert_eq!(chunk, &full[offset..end], "offset={offset} len={len}");
+
+            apply_keystream_at(SEED, offset, chunk);
+            assert_eq!(chunk, &original[offset..end]);
+        }
+    }
+
+    //Edit obfuscated data in place
+    let mut edited = full;
+    XorCipher::with_seed(SEED).obfuscate_at(5, &mut edited[5..10]);
+    assert_eq!(edited[5..10], original[5..10]);
+    edited[5..10].copy_from_slice(b"hello");
+    XorCipher::with_seed(SEED).obfuscate_at(5, &mut edited[5..10]);
+    XorCipher::with_seed(SEED).deobfuscate(&mut edited);
+    assert_eq!(&edited[5..10], b"hello");
+    assert_eq!(edited[..5], original[..5]);
+    assert_eq!(edited[10..], original[10..]);
+}
+
+#[cfg(feature = "rayon")]
+#[cfg_attr(miri, ignore)]
+#[test]
+fn should_verify_fisher_yates_shuffle_parallel() {
+    const BLOCK_SIZE: usize = 256;
+
+    let original: [u8; 4096 + 100] = core::array::from_fn(|idx| (idx % 251) as u8);
+
+    for seed in [0, 1, 0x1234, u64::MAX] {
+        let shuffle = FisherYates::with_seed(seed);
+
+        //Documented layout
+        let mut expected = original;
+        for (idx, block) in expected.chunks_mut(BLOCK_SIZE).enumerate() {
+            shuffle.block_shuffle(idx).shuffle(block);
+        }
+        shuffle.shuffle_blocks(&mut expected[..4096], BLOCK_SIZE);
+
+        let pool = obfus::rayon::ThreadPoolBuilder::new().num_threads(1).build().expect("to build pool");
+        let mut single = original;
+        pool.install(|| shuffle.shuffle_parallel(&mut single, BLOCK_SIZE));
+        assert_eq!(single, expected);
+
+        let mut parallel = original;
+        shuffle.shuffle_parallel(&mut parallel, BLOCK_SIZE);
+        assert_eq!(parallel, expected);
+        assert_ne!(parallel, original);
+
+        shuffle.reverse_parallel(&mut parallel, BLOCK_SIZE);
+        assert_eq!(parallel, original);
+    }
+
+    let shuffle = FisherYates::with_seed(1);
+    let mut small = *b"abc";
+    shuffle.shuffle_parallel(&mut small, BLOCK_SIZE);
+    shuffle.reverse_parallel(&mut small, BLOCK_SIZE);
+    assert_eq!(small, *b"abc");
+
+    let mut empty: [u8; 0] = [];
+    shuffle.shuffle_parallel(&mut empty, BLOCK_SIZE);
+    shuffle.reverse_parallel(&mut empty, BLOCK_SIZE);
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn should_verify_secure_memset_fast() {
+    let mut buffer = std::vec![0xffu8; 64 * 1024];
+    secure_memset_fast(&mut buffer, 0);
+    assert!(buffer.iter().all(|byte| *byte == 0));
+    secure_memset_fast(&mut buffer, 0x5a);
+    assert!(buffer.iter().all(|byte| *byte == 0x5a));
+
+    //Unaligned head and tail
+    let mut buffer = [0xffu8; 67];
+    secure_memset_fast(&mut buffer[3..], 1);
+    assert_eq!(buffer[..3], [0xff; 3]);
+    assert!(buffer[3..].iter().all(|byte| *byte == 1));
+    for len in 0..15 {
+        let mut buffer = [0xffu8; 16];
+        secure_memset_fast(&mut buffer[1..1 + len], 2);
+        assert!(buffer[1..1 + len].iter().all(|byte| *byte == 2));
+        assert!(buffer[1 + len..].iter().all(|byte| *byte == 0xff));
+        assert_eq!(buffer[0], 0xff);
+    }
+}
+
+#[test]
+fn should_verify_obfuscated_array() {
+    use obfus::xor::ObfuscatedArray;
+
+    const TABLE: [u32; 8] = [0xdeadbeef, 0, 1, 0x12345678, u32::MAX, 42, 0xcafebabe, 7];
+    static OBFUSCATED: ObfuscatedArray<u32, 8> = ObfuscatedArray::<u32, 8>::new(TABLE, 0x1234);
+
+    assert_eq!(OBFUSCATED.len(), 8);
+    for (idx, expected) in TABLE.iter().enumerate() {
+        assert_eq!(OBFUSCATED.get(idx), *expected);
+    }
+
+    let signed = ObfuscatedArray::<i16, 3>::new([-1, 0, i16::MIN], 1);
+    assert_eq!([signed.get(0), signed.get(1), signed.get(2)], [-1, 0, i16::MIN]);
+
+    let empty = ObfuscatedArray::<u8, 0>::new([], 1);
+    assert!(empty.is_empty());
+}
+
+#[test]
+#[should_panic]
+fn should_panic_on_obfuscated_array_out_of_bounds() {
+    use obfus::xor::ObfuscatedArray;
+
+    let array = ObfuscatedArray::<u64, 2>::new([1, 2], 1);
+    array.get(2);
+}
+
+#[cfg(feature = "hash")]
+#[test]
+fn should_derive_nonce_from_context() {
+    let first = crypto::derive_nonce(b"context", 0);
+    assert_eq!(first, crypto::derive_nonce(b"context", 0));
+    assert_ne!(first, crypto::derive_nonce(b"context", 1));
+    assert_ne!(first, crypto::derive_nonce(b"other", 0));
+    assert_ne!(crypto::derive_nonce(b"", 0), crypto::derive_nonce(b"", 1));
+
+    let mut nonces = std::collections::HashSet::new();
+    for counter in 0..1000 {
+        assert!(nonces.insert(crypto::derive_nonce(b"context", counter)));
+    }
+
+    let crypto = crypto::Crypto::new([1; 32]);
+    let mut buffer = crypto::Buffer::<{crypto::required_buffer_size(4)}>::from_slice(b"test").expect("to create");
+    crypto.encrypt(crypto::derive_nonce(b"context", 5), &mut buffer).expect("to encrypt");
+    crypto.decrypt(crypto::derive_nonce(b"context", 5), &mut buffer).expect("to decrypt");
+    assert_eq!(buffer.data(), b"test");
+}
+
+#[test]
+fn should_verify_secret_xor() {
+    use obfus::{Secret, Binary};
+
+    let original = obfus::secret_text!("xor secret");
+    let share_a = Secret::<10, Binary>::new(*b"0123456789");
+    let share_b = original.xor(&share_a);
+    assert_ne!(share_b.data(), original.data());
+
+    let recovered = share_a.xor(&share_b);
+    assert_eq!(recovered.data(), original.data());
+    assert_eq!(recovered.into_text().expect("utf-8").as_str(), "xor secret");
+
+    //Involution
+    assert_eq!(share_b.xor(&share_a).xor(&share_a).data(), share_b.data());
+    assert_eq!(share_a.xor(&share_a).data(), [0u8; 10]);
+}
+
+#[test]
+fn should_verify_secret_shares() {
+    use obfus::{Secret, Binary};
+
+    let original = Secret::<16, Binary>::new(*b"split me please!");
+    let (random_share, share) = Secret::<16, Binary>::new(*b"split me please!").split_shares(0x1234);
+    assert_ne!(random_share.data(), original.data());
+    assert_ne!(share.data(), original.data());
+    assert_ne!(random_share.data(), [0u8; 16]);
+
+    let combined = Secret::combine_shares(random_share, share);
+    assert_eq!(combined.data(), original.data());
+
+    let (left, right) = obfus::secret_text!("text").split_shares(1);
+    assert_eq!(Secret::combine_shares(right, left).into_text().expect("utf-8").as_str(), "text");
+
+    #[cfg(feature = "os_rng")]
+    {
+        let (random_share, share) = Secret::<16, Binary>::new(*b"split me please!").split_shares_random().expect("entropy");
+        assert_ne!(share.data(), original.data());
+        assert_eq!(Secret::combine_shares(random_share, share).data(), original.data());
+    }
+}
+
+#[test]
+fn should_verify_crypto_encrypt_to_and_decrypt_to() {
+    const KEY: [u8; 32] = [1; 32];
+    const NONCE: [u8; 12] = [2; 12];
+    const DATA: &[u8] = b"read-only";
+    type DataBuffer = crypto::Buffer<{crypto::required_buffer_size(DATA.len())}>;
+
+    let crypto = crypto::Crypto::new(KEY);
+    let mut encrypted = DataBuffer::new();
+    crypto.encrypt_to(NONCE, DATA, &mut encrypted).expect("to encrypt");
+    assert_eq!(encrypted.plaintext_len(), Some(DATA.len()));
+
+    //Ciphertext in read-only memory
+    static CIPHERTEXT: [u8; crypto::required_buffer_size(9)] = [
+        0x75, 0xb3, 0xa8, 0x2d, 0x67, 0x38, 0xaf, 0x91, 0xaa, 0x02, 0x1b, 0xbc, 0xc2, 0xc6, 0xc0, 0xe6,
+        0x5a, 0xce, 0x1a, 0x5a, 0xcf, 0x7a, 0x7f, 0xba, 0x70,
+    ];
+    assert_eq!(encrypted.data(), CIPHERTEXT);
+
+    let mut out = DataBuffer::from_slice(b"garbage").expect("to create");
+    crypto.decrypt_to(NONCE, &CIPHERTEXT, &mut out).expect("to decrypt");
+    assert_eq!(out.data(), DATA);
+
+    assert!(crypto.decrypt_to([3; 12], &CIPHERTEXT, &mut out).is_err());
+    assert!(crypto.decrypt_to(NONCE, &[0; crypto::required_buffer_size(DATA.len()) + 1], &mut out).is_err());
+    assert!(crypto.encrypt_to(NONCE, b"too long plaintext", &mut out).is_err());
+    //Plaintext cannot occupy space reserved for tag
+    assert!(crypto.encrypt_to(NONCE, &[0; 10], &mut out).is_err());
+}
+
+#[test]
+fn should_verify_secret_take_from() {
+    use obfus::{Secret, Binary};
+
+    let secret = Secret::<4, Binary>::from_array(*b"test");
+    assert_eq!(secret.data(), b"test");
+
+    let mut dma = *b"dma buffer";
+    let secret = Secret::<10, Binary>::take_from(&mut dma);
+    assert_eq!(dma, [0u8; 10]);
+    assert_eq!(secret.data(), b"dma buffer");
+}
+
+#[cfg(feature = "os_rng")]
+#[test]
+fn should_create_fisher_yates_from_entropy() {
+    let original: [u8; 64] = core::array::from_fn(|idx| idx as u8);
+
+    let first = FisherYates::from_entropy().expect("entropy");
+    let second = FisherYates::from_entropy().expect("entropy");
+    assert_ne!(first.seed(), second.seed());
+    assert_ne!(first.shuffle_const(original), second.shuffle_const(original));
+
+    let restored = FisherYates::with_seed(first.seed());
+    assert_eq!(restored.reverse_const(first.shuffle_const(original)), original);
+}
+
+#[test]
+fn should_verify_squares_stream_cipher() {
+    use crypto::SquaresStreamCipher;
+
+    const KEY: u64 = 0x7d8b63f54b86ca59;
+    let original: [u8; 64] = core::array::from_fn(|idx| idx as u8);
+
+    let mut data = original;
+    SquaresStreamCipher::new(KEY, 1).apply(&mut data);
+    assert_ne!(data, original);
+
+    //Splitting data across calls continues keystream
+    let mut chunked = original;
+    let mut cipher = SquaresStreamCipher::new(KEY, 1);
+    for chunk in chunked.chunks_mut(5) {
+        cipher.apply(chunk);
+    }
+    assert_eq!(chunked, data);
+
+    let mut other_nonce = original;
+    SquaresStreamCipher::new(KEY, 2).apply(&mut other_nonce);
+    assert_ne!(other_nonce, data);
+
+    SquaresStreamCipher::new(KEY, 1).apply(&mut data);
+    assert_eq!(data, original);
+    //Every bit of nonce selects distinct keystream
+    let mut low = [0u8; 64];
+    SquaresStreamCipher::new(KEY, 7).apply(&mut low);
+    let mut high = [0u8; 64];
+    SquaresStreamCipher::new(KEY, 7 | 1 << 32).apply(&mut high);
+    assert_ne!(low, high);
+    let mut max = [0u8; 64];
+    SquaresStreamCipher::new(KEY, u64::MAX).apply(&mut max);
+    let mut zero = [0u8; 64];
+    SquaresStreamCipher::new(KEY, 0).apply(&mut zero);
+    assert_ne!(max, zero);
+}
+
+#[test]
+fn should_verify_secure_fill_pattern() {
+    let mut data = [0u8; 7];
+    secure_fill_pattern(&mut data, &[0xDE, 0xAD]);
+    assert_eq!(data, [0xDE, 0xAD, 0xDE, 0xAD, 0xDE, 0xAD, 0xDE]);
+
+    secure_fill_pattern(&mut data, &[1, 2, 3]);
+    assert_eq!(data, [1, 2, 3, 1, 2, 3, 1]);
+
+    secure_fill_pattern(&mut data, &[]);
+    assert_eq!(data, [1, 2, 3, 1, 2, 3, 1]);
+
+    let mut empty = [0u8; 0];
+    secure_fill_pattern(&mut empty, &[0xDE, 0xAD]);
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn should_count_secret_wipes() {
+    use obfus::{Secret, Binary};
+
+    let before = obfus::testing::secret_wipes();
+    {
+        let _first = Secret::<4, Binary>::new(*b"test");
+        let _second = Secret::<4, Binary>::new(*b"tost");
+    }
+    assert!(obfus::testing::secret_wipes() >= before + 2);
+}
+
+#[test]
+fn should_convert_arrays_into_secret() {
+    use obfus::{Secret, Binary, Text};
+
+    let secret: Secret<4, Binary> = [1, 2, 3, 4].into();
+    assert_eq!(secret.data(), [1, 2, 3, 4]);
+
+    let text = Secret::<4, Text>::try_from(*b"test").expect("valid utf-8");
+    assert_eq!(text.as_str(), "test");
+
+    let error = Secret::<4, Text>::try_from([b't', 0xFF, b's', b't']).expect_err("invalid utf-8");
+    assert_eq!(error.valid_up_to(), 1);
+
+    fn convert<T: TryFrom<[u8; 4]>>(data: [u8; 4]) -> Option<T> {
+        T::try_from(data).ok()
+    }
+    assert!(convert::<Secret<4, Text>>(*b"test").is_some());
+    assert!(convert::<Secret<4, Text>>([0xFF; 4]).is_none());
+    assert!(convert::<Secret<4, Binary>>([0xFF; 4]).is_some());
+}
+
+#[test]
+fn should_calculate_buffer_size_for_many_records() {
+    const SIZE: usize = crypto::required_buffer_size_many(&[4, 8, 16]);
+    assert_eq!(SIZE, crypto::required_buffer_size(4) + crypto::required_buffer_size(8) + crypto::required_buffer_size(16));
+    assert_eq!(crypto::required_buffer_size_many(&[]), 0);
+    assert_eq!(crypto::required_buffer_size_many(&[usize::MAX, 1]), usize::MAX);
+}
+
+#[test]
+fn should_verify_opaque_predicates() {
+    use obfus::utils::{opaque_true, opaque_false};
+
+    let mut prng = obfus::prng::Squares::new(0x1234);
+    for seed in (0..1000).chain((0..1000).map(|_| prng.next())).chain([u64::MAX]) {
+        assert!(opaque_true(seed), "seed={seed}");
+        assert!(!opaque_false(seed), "seed={seed}");
+    }
+}
+
+#[test]
+fn should_verify_buffer_padding() {
+    const NONCE: [u8; 12] = [2; 12];
+    const SIZE: usize = crypto::required_buffer_size(32);
+
+    let crypto = crypto::Crypto::new([1; 32]);
+    let mut rng = obfus::prng::Squares::new(0x1234);
+
+    let mut buffer = crypto::Buffer::<SIZE>::from_slice(b"test").expect("to create");
+    buffer.pad_to(33, &mut rng).expect_err("cannot exceed capacity");
+    buffer.pad_to(7, &mut rng).expect_err("cannot fit length");
+    assert_eq!(buffer, b"test");
+
+    buffer.pad_to(32, &mut rng).expect("to pad");
+    assert_eq!(buffer.data().len(), 32);
+    assert_eq!(&buffer[..4], b"test");
+    assert_eq!(buffer.unpadded_len(), Some(4));
+
+    let mut other = crypto::Buffer::<SIZE>::from_slice(b"longer secret").expect("to create");
+    other.pad_to(32, &mut rng).expect("to pad");
+
+    crypto.encrypt(NONCE, &mut buffer).expect("to encrypt");
+    crypto.encrypt([3; 12], &mut other).expect("to encrypt");
+    assert_eq!(buffer.data().len(), other.data().len());
+
+    crypto.decrypt(NONCE, &mut buffer).expect("to decrypt");
+    let real_len = buffer.unpadded_len().expect("padded");
+    buffer.strip_padding(real_len).expect("to strip");
+    assert_eq!(buffer, b"test");
+    buffer.strip_padding(5).expect_err("cannot exceed written data");
+
+    let unpadded = crypto::Buffer::<SIZE>::from_slice(&[0xFF; 8]).expect("to create");
+    assert_eq!(unpadded.unpadded_len(), None);
+    assert_eq!(crypto::Buffer::<SIZE>::new().unpadded_len(), None);
+}
+
+#[test]
+fn should_verify_const_pipeline() {
+    use obfus::pipeline::Pipeline;
+
+    const PIPELINE: Pipeline = Pipeline::with_seed(0x1234);
+    const EMPTY: [u8; 0] = PIPELINE.obfuscate_const([]);
+    const SINGLE: [u8; 1] = PIPELINE.obfuscate_const(*b"s");
+    const TEXT: [u8; 11] = PIPELINE.obfuscate_const(*b"hello world");
+    const LARGE: [u8; 64] = PIPELINE.obfuscate_const([0x42; 64]);
+    const TEXT_REVERSED: [u8; 11] = PIPELINE.deobfuscate_const(TEXT);
+
+    assert_eq!(PIPELINE.deobfuscate_const(EMPTY), [0u8; 0]);
+    assert_ne!(SINGLE, *b"s");
+    assert_eq!(PIPELINE.deobfuscate_const(SINGLE), *b"s");
+    assert_ne!(TEXT, *b"hello world");
+    assert_eq!(TEXT_REVERSED, *b"hello world");
+    assert_ne!(LARGE, [0x42; 64]);
+    assert_eq!(PIPELINE.deobfuscate_const(LARGE), [0x42; 64]);
+
+    let mut text = TEXT;
+    PIPELINE.deobfuscate(&mut text);
+    assert_eq!(&text, b"hello world");
+    PIPELINE.obfuscate(&mut text);
+    assert_eq!(text, TEXT);
+
+    assert_ne!(Pipeline::with_seed(1).obfuscate_const(*b"hello world"), TEXT);
+}
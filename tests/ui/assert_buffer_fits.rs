@@ -0,0 +1,7 @@
+const DATA_LEN: usize = 8;
+const BUFFER_SIZE: usize = 16;
+
+obfus::crypto::assert_buffer_fits!(DATA_LEN, BUFFER_SIZE);
+
+fn main() {
+}
@@ -0,0 +1,4 @@
+fn main() {
+    let secret = obfus::Secret::<4, obfus::Binary>::new(*b"test");
+    let _ = format!("{}", secret);
+}
@@ -0,0 +1,3 @@
+fn main() {
+    let _secret = obfus::secret_from_env!("OBFUS_SURELY_MISSING_ENV_VAR", seed = 0x55);
+}
@@ -0,0 +1,4 @@
+fn main() {
+    let seed = 1u64;
+    let _ = obfus::shuffled_const!(seed = seed, b"secret");
+}